@@ -5,26 +5,61 @@ set -e # Exit on error
 # We use --quiet to reduce cargo build output noise
 BIN="cargo run -p sovereign-tee-core --quiet --"
 
+# Member keystores are unlocked non-interactively for the demo
+export SOVEREIGN_KEYSTORE_PASSPHRASE="e2e-demo-passphrase"
+
 echo -e "\n=== Test Scenario 1: Standard Seal Strategy ==="
 # Cleanup
-rm -f group.json dao.hex tee.hex dao_share.seal tee_share.store
+rm -rf keystore
+rm -f group.json proposals.json nonces.json dao.hex tee.hex dao_share.seal tee_share.store refresh_state.json spending.json policy.json audit.log audit.log.head tee_identity.key
 
 # 1. Init
 $BIN genesis-init --threshold 2
 $BIN genesis-join --name Alice
 $BIN genesis-join --name Bob
 $BIN genesis-launch
+if $BIN genesis-launch 2>/dev/null; then
+    echo "❌ Relaunched a launched group without --force"
+    exit 1
+fi
+$BIN group-migrate --dry-run
 
-# 2. Execute
-$BIN proposal-execute \
+# 2. Propose, vote (each member unlocks only their own keystore), then execute
+$BIN proposal-create --member Alice \
     --recipient "0x1111111111111111111111111111111111111111111111111111111111111111" \
     --amount 100
+$BIN proposal-vote --member Alice --id 1
+$BIN proposal-vote --member Bob --id 1
+if $BIN proposal-execute --executor Bob --id 1 2>/dev/null; then
+    echo "❌ Executed without a spending policy"
+    exit 1
+fi
+echo '{"max_per_transaction": 1000}' > policy.json
+$BIN proposal-execute --executor Bob --id 1
+
+# 3. The same proposal and votes cannot be executed twice
+if $BIN proposal-execute --executor Bob --id 1 2>/dev/null; then
+    echo "❌ Replayed proposal was executed"
+    exit 1
+fi
+$BIN proposal-list
+
+# 4. The audit log chains every step; a truncated log no longer verifies
+$BIN audit verify
+head -n 3 audit.log > audit.truncated
+cp audit.log.head audit.truncated.head
+if $BIN audit verify --audit-log audit.truncated 2>/dev/null; then
+    echo "❌ Truncated audit log verified"
+    exit 1
+fi
+rm -f audit.truncated audit.truncated.head
 
 echo "✅ Strategy A Passed"
 
 echo -e "\n=== Test Scenario 2: NFT Sharding Strategy ==="
 # Cleanup
-rm -f group.json dao.hex tee.hex dao_share.seal tee_share.store shard_*.hex
+rm -rf keystore
+rm -f group.json proposals.json nonces.json dao.hex tee.hex dao_share.seal tee_share.store refresh_state.json spending.json policy.json audit.log audit.log.head tee_identity.key tee_mpc.key shard_*.hex
 
 # 1. Init
 $BIN genesis-init --threshold 2
@@ -32,15 +67,32 @@ $BIN genesis-join --name Alice
 $BIN genesis-join --name Bob
 $BIN genesis-launch --strategy nft-sharding --shards 5
 
-# 2. Execute (with 2 shards)
-$BIN proposal-execute \
-    --strategy nft-sharding \
-    --shards-in shard_1.hex shard_2.hex \
+# 2. Propose, then execute (with 2 shards)
+$BIN proposal-create --member Alice \
     --recipient "0x2222222222222222222222222222222222222222222222222222222222222222" \
     --amount 200
+$BIN proposal-execute --executor Bob --id 1 --no-policy \
+    --strategy nft-sharding \
+    --shards-in shard_1.hex shard_2.hex
+
+# 3. Refresh (replaces every shard and the TEE share), then execute again
+$BIN group-refresh \
+    --strategy nft-sharding \
+    --shards 5 \
+    --shards-in shard_1.hex shard_2.hex shard_3.hex shard_4.hex shard_5.hex \
+    --tee-out tee_share.store
+
+$BIN proposal-create --member Bob \
+    --recipient "0x2222222222222222222222222222222222222222222222222222222222222222" \
+    --amount 300
+$BIN proposal-execute --executor Alice --id 2 --no-policy \
+    --strategy nft-sharding \
+    --shards-in shard_3.hex shard_5.hex
+$BIN audit verify
 
 echo "✅ Strategy B Passed"
 
 # Cleanup
-rm -f group.json dao.hex tee.hex dao_share.seal tee_share.store shard_*.hex
+rm -rf keystore
+rm -f group.json proposals.json nonces.json dao.hex tee.hex dao_share.seal tee_share.store refresh_state.json spending.json policy.json audit.log audit.log.head tee_identity.key tee_mpc.key shard_*.hex
 echo -e "\n=== All System Tests Passed Successfully ==="
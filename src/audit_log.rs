@@ -0,0 +1,389 @@
+use anyhow::{Result, anyhow};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+
+use crate::dao::{DelegationScope, GovernanceAction, MemberKey};
+use crate::sui_utils::{SignatureScheme, verify_personal_message};
+
+// Append-only event log, one JSON entry per line. Each entry commits to the
+// previous one:
+//
+//   hash = Blake2b256("SOVEREIGN_AUDIT_V1" || BCS(seq, timestamp, event, prev_hash))
+//
+// so editing or dropping an entry breaks every later hash. Entries written
+// where the TEE identity key is available carry its personal-message signature
+// over `hash`; once the vault is launched every entry must be. A head file
+// (`<log>.head`) records the length and last hash, signed by the TEE as
+//
+//   Blake2b256("SOVEREIGN_AUDIT_HEAD_V1" || BCS(len, hash))
+//
+// so dropping entries from the end is caught by comparing against it, or
+// against a head published elsewhere. The TEE identity is pinned outside the
+// log (`tee_address` in the group file); a log that only vouches for itself
+// proves nothing.
+
+const AUDIT_DOMAIN: &[u8] = b"SOVEREIGN_AUDIT_V1";
+const AUDIT_HEAD_DOMAIN: &[u8] = b"SOVEREIGN_AUDIT_HEAD_V1";
+
+/// `prev_hash` of the first entry.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Something that changed the DAO or used its key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AuditEvent {
+    GenesisInit {
+        threshold: u64,
+        quorum_weight: u64,
+        membership_digest: String,
+    },
+    MemberJoined {
+        name: String,
+        address: String,
+        scheme: SignatureScheme,
+        weight: u64,
+    },
+    /// The vault exists; `tee_address` is the identity that signs later entries.
+    Launched {
+        vault_address: String,
+        strategy: String,
+        membership_digest: String,
+        tee_address: String,
+    },
+    GovernanceApplied {
+        action: GovernanceAction,
+        approvers: Vec<String>,
+        membership_digest: String,
+    },
+    DelegationCreated {
+        id: String,
+        delegator: String,
+        delegate: String,
+        scope: DelegationScope,
+        expires_at: u64,
+    },
+    DelegationRevoked {
+        id: String,
+    },
+    ProposalCreated {
+        id: u64,
+        proposer: String,
+        vote_digest: String,
+        recipient: String,
+        amount: u64,
+    },
+    VoteCast {
+        id: u64,
+        voter: String,
+        signature: String,
+    },
+    VetoCast {
+        id: u64,
+        guardian: String,
+        signature: String,
+    },
+    ProposalExecuted {
+        id: u64,
+        executor: String,
+        tx_digest: String,
+        signature: String,
+    },
+    SharesRefreshed {
+        epoch: u64,
+        reason: String,
+    },
+    BreakGlassExported {
+        vault_address: String,
+        approvers: Vec<String>,
+    },
+    /// Written before the vault signs; replays are checked against it.
+    NonceConsumed {
+        dao_id: String,
+        nonce: u64,
+    },
+    /// A launched group was launched again with `--force`, replacing its vault.
+    Relaunched {
+        previous_vault: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub event: AuditEvent,
+    /// Hex hash of the previous entry; `GENESIS_HASH` for the first.
+    pub prev_hash: String,
+    pub hash: String,
+    /// TEE identity's personal-message signature over `hash`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tee_signature: Option<String>,
+}
+
+impl AuditEntry {
+    /// The hash the entry's contents commit to.
+    pub fn compute_hash(&self) -> Result<String> {
+        let mut data = AUDIT_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(&(
+            self.seq,
+            self.timestamp,
+            &self.event,
+            &self.prev_hash,
+        ))?);
+        Ok(hex::encode(Blake2b256::digest(&data).digest))
+    }
+}
+
+/// Length and last hash of a log, kept apart from it to catch truncation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditHead {
+    pub len: u64,
+    pub hash: String,
+    /// TEE identity's personal-message signature over `digest()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tee_signature: Option<String>,
+}
+
+impl AuditHead {
+    /// What the TEE signs; the signature itself is not covered.
+    pub fn digest(&self) -> Result<Vec<u8>> {
+        let mut data = AUDIT_HEAD_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(&(self.len, &self.hash))?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+}
+
+/// What `AuditLog::verify` checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditReport {
+    pub entries: usize,
+    pub signed: usize,
+    pub tee_address: Option<String>,
+    /// Whether the log contains a `Launched` entry.
+    pub launched: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Starts empty when the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(anyhow!("Failed to read audit log '{}': {}", path, e)),
+        };
+        let entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| anyhow!("Audit log line {} is not an entry: {}", i + 1, e))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    pub fn head_path(path: &str) -> String {
+        format!("{}.head", path)
+    }
+
+    /// Reads the head file next to `path`, if there is one.
+    pub fn load_head(path: &str) -> Result<Option<AuditHead>> {
+        match fs::read_to_string(Self::head_path(path)) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!("Failed to read audit head: {}", e)),
+        }
+    }
+
+    pub fn head(&self) -> AuditHead {
+        AuditHead {
+            len: self.entries.len() as u64,
+            hash: self
+                .entries
+                .last()
+                .map_or(GENESIS_HASH.to_string(), |e| e.hash.clone()),
+            tee_signature: None,
+        }
+    }
+
+    /// The TEE identity announced by the first `Launched` entry.
+    pub fn launched_tee(&self) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match &entry.event {
+            AuditEvent::Launched { tee_address, .. } => Some(tee_address.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Chains `event` onto the log, signed by `tee` when given. After launch
+    /// the entry must be signed by the launched TEE identity.
+    pub fn append(
+        &mut self,
+        event: AuditEvent,
+        now: u64,
+        tee: Option<&MemberKey>,
+    ) -> Result<&AuditEntry> {
+        if let Some(launched) = self.launched_tee() {
+            let signer = tee
+                .ok_or_else(|| anyhow!("The vault is launched; audit entries must be TEE-signed"))?
+                .member()?
+                .address;
+            if signer != launched {
+                return Err(anyhow!(
+                    "TEE identity {} is not the launched identity {}",
+                    signer,
+                    launched
+                ));
+            }
+        }
+        let head = self.head();
+        let mut entry = AuditEntry {
+            seq: head.len,
+            timestamp: now,
+            event,
+            prev_hash: head.hash,
+            hash: String::new(),
+            tee_signature: None,
+        };
+        entry.hash = entry.compute_hash()?;
+        if let Some(tee) = tee {
+            entry.tee_signature = Some(tee.sign_personal_message(&hex::decode(&entry.hash)?)?);
+        }
+        self.entries.push(entry);
+        Ok(self.entries.last().expect("just pushed"))
+    }
+
+    /// Appends one entry to the file at `path` and updates its head file.
+    /// Earlier lines are never rewritten.
+    pub fn append_to_file(
+        path: &str,
+        event: AuditEvent,
+        now: u64,
+        tee: Option<&MemberKey>,
+    ) -> Result<AuditEntry> {
+        let mut log = Self::load(path)?;
+        let entry = log.append(event, now, tee)?.clone();
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow!("Failed to open audit log '{}': {}", path, e))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        let mut head = log.head();
+        if let Some(tee) = tee {
+            head.tee_signature = Some(tee.sign_personal_message(&head.digest()?)?);
+        }
+        fs::write(Self::head_path(path), serde_json::to_string_pretty(&head)?)?;
+        Ok(entry)
+    }
+
+    /// Checks sequence numbers, the hash chain and TEE signatures, and that the
+    /// log ends at `expected_head` when one is given (and is signed by the TEE,
+    /// if it carries a signature). Signatures must come from `expected_tee`,
+    /// which every `Launched` entry must announce; without it the first
+    /// `Launched` entry is trusted. Every entry from launch on must be signed.
+    pub fn verify(
+        &self,
+        expected_head: Option<&AuditHead>,
+        expected_tee: Option<&str>,
+    ) -> Result<AuditReport> {
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut tee_address = expected_tee.map(str::to_string);
+        let mut launched = false;
+        let mut signed = 0;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if entry.seq != i as u64 {
+                return Err(anyhow!(
+                    "Entry {} has sequence number {}: entries were removed or reordered",
+                    i,
+                    entry.seq
+                ));
+            }
+            if entry.prev_hash != prev_hash {
+                return Err(anyhow!(
+                    "Entry {} does not follow entry {}: the chain is broken",
+                    i,
+                    i.saturating_sub(1)
+                ));
+            }
+            if entry.compute_hash()? != entry.hash {
+                return Err(anyhow!("Entry {} was modified: its hash does not match", i));
+            }
+            if let AuditEvent::Launched {
+                tee_address: launched,
+                ..
+            } = &entry.event
+            {
+                if *tee_address.get_or_insert(launched.clone()) != *launched {
+                    return Err(anyhow!(
+                        "Entry {} launches TEE identity {}, but {} was expected",
+                        i,
+                        launched,
+                        tee_address.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+            launched |= matches!(entry.event, AuditEvent::Launched { .. });
+            if launched && entry.tee_signature.is_none() {
+                return Err(anyhow!(
+                    "Entry {} follows the launch but is not signed by the TEE",
+                    i
+                ));
+            }
+            if let Some(signature) = &entry.tee_signature {
+                let signer = verify_personal_message(&hex::decode(&entry.hash)?, signature)
+                    .map_err(|e| anyhow!("Entry {} has an invalid TEE signature: {}", i, e))?;
+                if tee_address.as_deref() != Some(signer.as_str()) {
+                    return Err(anyhow!(
+                        "Entry {} is signed by {}, not the launched TEE identity",
+                        i,
+                        signer
+                    ));
+                }
+                signed += 1;
+            }
+            prev_hash = entry.hash.clone();
+        }
+        if let (Some(expected), false) = (expected_tee, launched) {
+            return Err(anyhow!(
+                "Log never launches TEE identity {}: the launch was removed",
+                expected
+            ));
+        }
+        if let Some(expected) = expected_head {
+            let head = self.head();
+            if (head.len, &head.hash) != (expected.len, &expected.hash) {
+                return Err(anyhow!(
+                    "Log has {} entries ending at {}, but the head records {} ending at {}: \
+                     the log was truncated or rewritten",
+                    head.len,
+                    head.hash,
+                    expected.len,
+                    expected.hash
+                ));
+            }
+            if let Some(signature) = &expected.tee_signature {
+                let signer = verify_personal_message(&expected.digest()?, signature)
+                    .map_err(|e| anyhow!("The head has an invalid TEE signature: {}", e))?;
+                if tee_address.as_deref() != Some(signer.as_str()) {
+                    return Err(anyhow!(
+                        "The head is signed by {}, not the TEE identity",
+                        signer
+                    ));
+                }
+            }
+        }
+        Ok(AuditReport {
+            entries: self.entries.len(),
+            signed,
+            tee_address,
+            launched,
+        })
+    }
+}
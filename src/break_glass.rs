@@ -0,0 +1,306 @@
+use anyhow::{Result, anyhow};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use hkdf::Hkdf;
+use k256::ecdsa::{SigningKey, VerifyingKey};
+use k256::elliptic_curve::Field;
+use k256::elliptic_curve::point::AffineCoordinates;
+use k256::sha2::Sha256;
+use k256::{ProjectivePoint, PublicKey, Scalar};
+use rand_core::{CryptoRng, OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use zeroize::Zeroizing;
+
+use crate::dao::{DaoGroup, Permission, ProposalKind};
+use crate::secret::SecretScalar;
+use crate::share_codec::scalar_from_be_bytes;
+use crate::sui_utils::pubkey_to_sui_address;
+
+// Break-glass export: the only sanctioned way to take the vault key out of the
+// DAO/TEE split (legal wind-down, TEE loss).
+//
+// 1. A dated `ExportRequest` names the vault, the recovery public key and a reason.
+// 2. A super-majority of members signs the request digest as a Sui personal message.
+// 3. The key is encrypted to the recovery key (ECDH on secp256k1 + HKDF-SHA256 +
+//    ChaCha20-Poly1305, with the request digest as associated data) and an
+//    `ExportRecord` lists who approved it.
+
+const EXPORT_DOMAIN: &[u8] = b"SOVEREIGN_BREAK_GLASS_V1";
+const HKDF_INFO: &[u8] = b"sovereign-break-glass-v1/chacha20poly1305";
+
+/// What the members are asked to approve.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExportRequest {
+    pub vault_address: String,
+    /// Compressed secp256k1 public key the vault key is encrypted to.
+    pub recovery_pubkey_hex: String,
+    pub reason: String,
+    pub requested_at: u64,
+    pub expires_at: u64,
+}
+
+impl ExportRequest {
+    pub fn new(
+        vault_address: &str,
+        recovery_pubkey_hex: &str,
+        reason: &str,
+        now: u64,
+        valid_for_secs: u64,
+    ) -> Result<Self> {
+        if reason.trim().is_empty() {
+            return Err(anyhow!("An export request must state a reason"));
+        }
+        parse_recovery_key(recovery_pubkey_hex)?;
+        Ok(Self {
+            vault_address: vault_address.to_string(),
+            recovery_pubkey_hex: recovery_pubkey_hex.to_string(),
+            reason: reason.to_string(),
+            requested_at: now,
+            expires_at: now.saturating_add(valid_for_secs),
+        })
+    }
+
+    /// The message members sign: Blake2b256(domain || BCS(request)).
+    pub fn digest(&self) -> Result<Vec<u8>> {
+        let mut data = EXPORT_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(self)?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    pub fn check_window(&self, now: u64) -> Result<()> {
+        if now < self.requested_at {
+            return Err(anyhow!("Export request is dated in the future"));
+        }
+        if now > self.expires_at {
+            return Err(anyhow!("Export request expired at {}", self.expires_at));
+        }
+        Ok(())
+    }
+}
+
+/// One member's approval, kept verbatim for the audit record.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Approval {
+    pub address: String,
+    pub member: String,
+    pub pubkey_hex: String,
+    pub signature: String,
+}
+
+/// The encrypted vault key plus everything needed to audit the export.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExportBundle {
+    pub request: ExportRequest,
+    pub approvals: Vec<Approval>,
+    pub ephemeral_pubkey_hex: String,
+    pub nonce_hex: String,
+    pub ciphertext_hex: String,
+}
+
+/// Who approved which export, without the ciphertext.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExportRecord {
+    pub request_digest: String,
+    pub request: ExportRequest,
+    pub approvals: Vec<Approval>,
+    pub required_approvals: usize,
+    pub total_members: usize,
+    pub exported_at: u64,
+}
+
+/// Super-majority: at least two thirds of the voting members, and never below the threshold.
+pub fn required_approvals(group: &DaoGroup) -> usize {
+    let two_thirds = (voting_members(group) * 2).div_ceil(3);
+    two_thirds.max(group.threshold)
+}
+
+/// Checks the request window and the super-majority; returns the valid approvals.
+pub fn verify_export_approvals(
+    group: &DaoGroup,
+    request: &ExportRequest,
+    signatures: &HashMap<String, String>,
+    now: u64,
+) -> Result<Vec<Approval>> {
+    request.check_window(now)?;
+    let digest = request.digest()?;
+    let approvers: Vec<String> = group
+        .verify_proposal(&digest, signatures, ProposalKind::BreakGlass, now)
+        .valid_voters
+        .into_iter()
+        .map(|voter| voter.address)
+        .collect();
+
+    let required = required_approvals(group);
+    if approvers.len() < required {
+        return Err(anyhow!(
+            "Break-glass export needs {} of {} voting members, got {}",
+            required,
+            voting_members(group),
+            approvers.len()
+        ));
+    }
+
+    Ok(approvers
+        .into_iter()
+        .filter_map(|address| {
+            let member = group.member(&address)?;
+            Some(Approval {
+                signature: signatures.get(&address)?.clone(),
+                member: member.name.clone(),
+                pubkey_hex: member.pubkey_hex.clone(),
+                address,
+            })
+        })
+        .collect())
+}
+
+/// Runs the ceremony: verifies approvals, checks `key` controls the requested vault,
+/// and encrypts it to the recovery key.
+pub fn export_vault_key(
+    group: &DaoGroup,
+    request: &ExportRequest,
+    signatures: &HashMap<String, String>,
+    key: &SecretScalar,
+    now: u64,
+) -> Result<(ExportBundle, ExportRecord)> {
+    export_vault_key_with_rng(group, request, signatures, key, now, &mut OsRng)
+}
+
+pub fn export_vault_key_with_rng<R: CryptoRng + RngCore>(
+    group: &DaoGroup,
+    request: &ExportRequest,
+    signatures: &HashMap<String, String>,
+    key: &SecretScalar,
+    now: u64,
+    rng: &mut R,
+) -> Result<(ExportBundle, ExportRecord)> {
+    let approvals = verify_export_approvals(group, request, signatures, now)?;
+
+    let signing_key = SigningKey::from_bytes(&key.expose_secret().to_bytes())
+        .map_err(|e| anyhow!("Invalid vault key: {}", e))?;
+    let address = pubkey_to_sui_address(&VerifyingKey::from(&signing_key));
+    if address != request.vault_address {
+        return Err(anyhow!(
+            "Shares control {}, but the request is for {}",
+            address,
+            request.vault_address
+        ));
+    }
+
+    let recovery = parse_recovery_key(&request.recovery_pubkey_hex)?;
+    let digest = request.digest()?;
+
+    let ephemeral = Zeroizing::new(Scalar::random(&mut *rng));
+    let ephemeral_point = ProjectivePoint::GENERATOR * *ephemeral;
+    let ephemeral_pubkey = encode_point(&ephemeral_point);
+    let cipher = derive_cipher(&(recovery * *ephemeral), &ephemeral_pubkey)?;
+
+    let mut nonce = [0u8; 12];
+    rng.fill_bytes(&mut nonce);
+    let plaintext = Zeroizing::new(key.expose_secret().to_bytes());
+    let ciphertext = cipher
+        .encrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: &plaintext,
+                aad: &digest,
+            },
+        )
+        .map_err(|_| anyhow!("Failed to encrypt vault key"))?;
+
+    let record = ExportRecord {
+        request_digest: hex::encode(&digest),
+        request: request.clone(),
+        approvals: approvals.clone(),
+        required_approvals: required_approvals(group),
+        total_members: group.members.len(),
+        exported_at: now,
+    };
+    let bundle = ExportBundle {
+        request: request.clone(),
+        approvals,
+        ephemeral_pubkey_hex: hex::encode(ephemeral_pubkey),
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+    };
+    Ok((bundle, record))
+}
+
+/// Recovery side: decrypts the bundle and checks the key matches the requested vault.
+pub fn open_export(bundle: &ExportBundle, recovery_key: &SecretScalar) -> Result<SecretScalar> {
+    let recovery_point = ProjectivePoint::GENERATOR * recovery_key.expose_secret();
+    if hex::encode(encode_point(&recovery_point)) != bundle.request.recovery_pubkey_hex {
+        return Err(anyhow!(
+            "This bundle is encrypted to a different recovery key"
+        ));
+    }
+
+    let ephemeral_pubkey = hex::decode(&bundle.ephemeral_pubkey_hex)?;
+    let ephemeral = PublicKey::from_sec1_bytes(&ephemeral_pubkey)
+        .map_err(|e| anyhow!("Invalid ephemeral public key: {}", e))?;
+    let shared = ephemeral.to_projective() * recovery_key.expose_secret();
+    let cipher = derive_cipher(&shared, &ephemeral_pubkey)?;
+
+    let nonce: [u8; 12] = hex::decode(&bundle.nonce_hex)?
+        .try_into()
+        .map_err(|_| anyhow!("Invalid nonce length"))?;
+    let ciphertext = hex::decode(&bundle.ciphertext_hex)?;
+    let digest = bundle.request.digest()?;
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &digest,
+                },
+            )
+            .map_err(|_| anyhow!("Bundle failed authentication; wrong key or tampered"))?,
+    );
+
+    let key = SecretScalar::new(
+        scalar_from_be_bytes(&plaintext).map_err(|e| anyhow!("Invalid exported key: {}", e))?,
+    );
+    let signing_key = SigningKey::from_bytes(&key.expose_secret().to_bytes())
+        .map_err(|e| anyhow!("Invalid exported key: {}", e))?;
+    if pubkey_to_sui_address(&VerifyingKey::from(&signing_key)) != bundle.request.vault_address {
+        return Err(anyhow!("Exported key does not control the requested vault"));
+    }
+    Ok(key)
+}
+
+fn parse_recovery_key(hex_str: &str) -> Result<ProjectivePoint> {
+    let bytes = hex::decode(hex_str).map_err(|_| anyhow!("Recovery public key is not hex"))?;
+    if bytes.len() != 33 {
+        return Err(anyhow!(
+            "Recovery public key must be a compressed secp256k1 key"
+        ));
+    }
+    let key = PublicKey::from_sec1_bytes(&bytes)
+        .map_err(|e| anyhow!("Invalid recovery public key: {}", e))?;
+    Ok(key.to_projective())
+}
+
+fn encode_point(point: &ProjectivePoint) -> Vec<u8> {
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    point.to_affine().to_encoded_point(true).as_bytes().to_vec()
+}
+
+fn derive_cipher(shared: &ProjectivePoint, ephemeral_pubkey: &[u8]) -> Result<ChaCha20Poly1305> {
+    let shared_x = Zeroizing::new(shared.to_affine().x());
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(ephemeral_pubkey), &shared_x[..])
+        .expand(HKDF_INFO, key.as_mut())
+        .map_err(|_| anyhow!("Key derivation failed"))?;
+    Ok(ChaCha20Poly1305::new(&Key::from(*key)))
+}
+
+fn voting_members(group: &DaoGroup) -> usize {
+    group
+        .members
+        .iter()
+        .filter(|m| m.can(Permission::Vote))
+        .count()
+}
@@ -1,80 +1,1065 @@
-use k256::ecdsa::signature::{Signer, Verifier};
-use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use k256::ecdsa::{SigningKey, VerifyingKey};
 use k256::elliptic_curve::group::GroupEncoding;
 use serde::{Deserialize, Serialize}; // For to_encoded_point
 // Use rand_core explicitly to match k256 dependency requirement
 use anyhow::{Result, anyhow};
-use rand_core::OsRng;
-use std::collections::HashMap;
+use fastcrypto::ed25519::{Ed25519KeyPair, Ed25519PrivateKey};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use fastcrypto::secp256r1::{Secp256r1KeyPair, Secp256r1PrivateKey};
+use fastcrypto::traits::{KeyPair, Signer, ToFromBytes};
+use rand_core::{CryptoRng, OsRng, RngCore};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Serialize, Deserialize, Clone)]
+use crate::multisig::MultiSigPublicKey;
+use crate::secret::SecretBytes;
+use crate::sui_utils::{
+    SignatureScheme, normalize_public_key, personal_message_digest, public_key_to_sui_address,
+    serialize_signature, sign_personal_message, verify_personal_message,
+};
+
+const GOVERNANCE_DOMAIN: &[u8] = b"SOVEREIGN_GOVERNANCE_V1";
+const DELEGATION_DOMAIN: &[u8] = b"SOVEREIGN_DELEGATION_V1";
+const REVOCATION_DOMAIN: &[u8] = b"SOVEREIGN_DELEGATION_REVOKE_V1";
+
+fn default_weight() -> u64 {
+    1
+}
+
+/// What a member may do. Members without explicit roles keep the original,
+/// all-purpose set: propose, vote and execute.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Proposer,
+    Voter,
+    Executor,
+    Guardian,
+    Auditor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    /// Draft a transfer proposal.
+    Propose,
+    /// Sign approvals; the signer's weight counts toward the quorum.
+    Vote,
+    /// Run the TEE signing step for a passed proposal.
+    Execute,
+    /// Block a passed proposal.
+    Veto,
+    /// Read-only access to governance records.
+    Audit,
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [
+        Role::Proposer,
+        Role::Voter,
+        Role::Executor,
+        Role::Guardian,
+        Role::Auditor,
+    ];
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::Proposer => &[Permission::Propose],
+            Role::Voter => &[Permission::Vote],
+            Role::Executor => &[Permission::Execute],
+            Role::Guardian => &[Permission::Vote, Permission::Veto],
+            Role::Auditor => &[Permission::Audit],
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::Proposer => "proposer",
+            Role::Voter => "voter",
+            Role::Executor => "executor",
+            Role::Guardian => "guardian",
+            Role::Auditor => "auditor",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Role::ALL
+            .into_iter()
+            .find(|r| r.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(anyhow!(
+                "Unknown role '{}' (expected proposer, voter, executor, guardian or auditor)",
+                s
+            ))
+    }
+}
+
+fn default_roles() -> BTreeSet<Role> {
+    BTreeSet::from([Role::Proposer, Role::Voter, Role::Executor])
+}
+
+/// Member names also name keystore files, so they are limited to `[A-Za-z0-9_-]`.
+pub fn validate_member_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+    {
+        return Err(anyhow!(
+            "Invalid member name '{}': use only letters, digits, '_' and '-'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Public membership data. Private keys live in per-member keystores (`keystore.rs`).
+///
+/// Members are identified by their Sui address, as in the on-chain `sovereign_dao::dao`
+/// contract; `name` is only a label.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Member {
+    pub address: String,
     pub name: String,
     pub pubkey_hex: String,
-    // In a real CLI, we might store private keys in separate files,
-    // but for this "Scenario Simulation", we keep them here for ease of use.
-    pub privkey_hex: String,
+    /// Scheme of `pubkey_hex`; groups written before it existed are secp256k1.
+    #[serde(default)]
+    pub scheme: SignatureScheme,
+    /// Voting power; founding members may carry more than one vote.
+    #[serde(default = "default_weight")]
+    pub weight: u64,
+    #[serde(default = "default_roles")]
+    pub roles: BTreeSet<Role>,
 }
 
 impl Member {
-    pub fn new(name: &str) -> Self {
-        // Use OsRng from rand_core v0.6 which implements CryptoRngCore for k256
-        let signing_key = SigningKey::random(&mut OsRng);
-        let verifying_key = VerifyingKey::from(&signing_key);
+    pub fn from_pubkey(name: &str, scheme: SignatureScheme, pubkey_hex: &str) -> Result<Self> {
+        let pubkey = normalize_public_key(scheme, &hex::decode(pubkey_hex)?)
+            .map_err(|e| anyhow!("Invalid pubkey for {}: {}", name, e))?;
+        Ok(Self {
+            address: public_key_to_sui_address(scheme, &pubkey),
+            name: name.to_string(),
+            pubkey_hex: hex::encode(pubkey),
+            scheme,
+            weight: default_weight(),
+            roles: default_roles(),
+        })
+    }
 
-        // k256 SigningKey to_bytes returns FieldBytes
-        let priv_bytes = signing_key.to_bytes();
-        let pub_bytes = verifying_key.to_encoded_point(true).as_bytes().to_vec();
+    pub fn can(&self, permission: Permission) -> bool {
+        self.roles
+            .iter()
+            .any(|r| r.permissions().contains(&permission))
+    }
+}
 
+/// A change to the group's membership or voting rules; the current quorum must approve it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum GovernanceAction {
+    SetWeight {
+        address: String,
+        weight: u64,
+    },
+    SetQuorumWeight {
+        weight: u64,
+    },
+    SetRoles {
+        address: String,
+        roles: BTreeSet<Role>,
+    },
+    /// Distinct approvers holding `role` needed on top of the weight quorum; 0 removes it.
+    SetRoleThreshold {
+        role: Role,
+        count: usize,
+    },
+    RemoveMember {
+        address: String,
+    },
+    /// Replaces a member's key, possibly with one of another scheme; the member
+    /// keeps its name, weight and roles under the address of the new key.
+    RotateKey {
+        address: String,
+        new_pubkey_hex: String,
+        #[serde(default)]
+        new_scheme: SignatureScheme,
+    },
+    /// Signing threshold (and Shamir k for NFT shards).
+    SetThreshold {
+        threshold: usize,
+    },
+    SetTimelock {
+        timelock: Timelock,
+    },
+    /// Admits a member to a launched group; before launch, `group-add-member` does this.
+    AddMember {
+        member: Member,
+    },
+}
+
+/// One applied governance action, kept in the group file.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GovernanceRecord {
+    pub action: GovernanceAction,
+    /// Addresses whose approval was counted.
+    pub approvers: Vec<String>,
+    pub membership_digest_before: String,
+    pub membership_digest_after: String,
+    pub applied_at: u64,
+}
+
+/// Delay between a proposal passing and its earliest execution, during which
+/// guardians may veto it. A zero delay executes passed proposals at once.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timelock {
+    pub delay_secs: u64,
+    /// Distinct guardian vetoes that block a proposal.
+    pub veto_threshold: usize,
+}
+
+impl Default for Timelock {
+    fn default() -> Self {
         Self {
+            delay_secs: 0,
+            veto_threshold: 1,
+        }
+    }
+}
+
+/// How member approvals are checked before the TEE signs.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GovernanceMode {
+    /// Tallied by `verify_proposal`; the Seal policy releases the DAO share.
+    #[default]
+    Seal,
+    /// Combined into a native Sui MultiSig authenticator (`multisig.rs`) that
+    /// anyone can check on-chain.
+    MultiSig,
+}
+
+impl fmt::Display for GovernanceMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GovernanceMode::Seal => write!(f, "seal"),
+            GovernanceMode::MultiSig => write!(f, "multisig"),
+        }
+    }
+}
+
+impl FromStr for GovernanceMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "seal" => Ok(GovernanceMode::Seal),
+            "multisig" => Ok(GovernanceMode::MultiSig),
+            _ => Err(anyhow!(
+                "Unknown governance mode '{}' (expected seal or multisig)",
+                s
+            )),
+        }
+    }
+}
+
+/// What a set of signatures approves; decides which delegations apply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalKind {
+    Transfer,
+    Governance,
+    /// Never delegated: each export approver signs in person.
+    BreakGlass,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DelegationScope {
+    All,
+    Transfers,
+    Governance,
+}
+
+impl DelegationScope {
+    pub fn covers(self, kind: ProposalKind) -> bool {
+        matches!(
+            (self, kind),
+            (
+                DelegationScope::All,
+                ProposalKind::Transfer | ProposalKind::Governance
+            ) | (DelegationScope::Transfers, ProposalKind::Transfer)
+                | (DelegationScope::Governance, ProposalKind::Governance)
+        )
+    }
+
+    fn overlaps(self, other: DelegationScope) -> bool {
+        self == other || self == DelegationScope::All || other == DelegationScope::All
+    }
+}
+
+impl fmt::Display for DelegationScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DelegationScope::All => "all",
+            DelegationScope::Transfers => "transfers",
+            DelegationScope::Governance => "governance",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for DelegationScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        [
+            DelegationScope::All,
+            DelegationScope::Transfers,
+            DelegationScope::Governance,
+        ]
+        .into_iter()
+        .find(|scope| scope.to_string().eq_ignore_ascii_case(s.trim()))
+        .ok_or(anyhow!(
+            "Unknown delegation scope '{}' (expected all, transfers or governance)",
+            s
+        ))
+    }
+}
+
+/// Default lifetime of a delegation.
+pub const DEFAULT_DELEGATION_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// A member's signed grant of their voting weight to another member, for one
+/// DAO and until `expires_at`. Delegations are not transitive: only the
+/// delegate's own signature carries the delegator's weight.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Delegation {
+    /// The DAO's vault address.
+    pub dao_id: String,
+    pub delegator: String,
+    pub delegate: String,
+    pub scope: DelegationScope,
+    pub expires_at: u64,
+    /// Delegator's personal-message signature over `digest()`.
+    pub signature: String,
+}
+
+impl Delegation {
+    pub fn create(
+        group: &DaoGroup,
+        delegator: &MemberKey,
+        delegate: &str,
+        scope: DelegationScope,
+        expires_at: u64,
+    ) -> Result<Self> {
+        let mut delegation = Self {
+            dao_id: group
+                .vault_address
+                .clone()
+                .ok_or(anyhow!("The group has no vault address; launch first"))?,
+            delegator: delegator.member()?.address,
+            delegate: delegate.to_string(),
+            scope,
+            expires_at,
+            signature: String::new(),
+        };
+        delegation.signature = delegator.sign_personal_message(&delegation.digest()?)?;
+        Ok(delegation)
+    }
+
+    /// Blake2b256(domain || BCS(every field but the signature)).
+    pub fn digest(&self) -> Result<Vec<u8>> {
+        let mut data = DELEGATION_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(&(
+            &self.dao_id,
+            &self.delegator,
+            &self.delegate,
+            self.scope,
+            self.expires_at,
+        ))?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    /// Hex digest; names the delegation for revocation.
+    pub fn id(&self) -> Result<String> {
+        Ok(hex::encode(self.digest()?))
+    }
+
+    /// The message the delegator signs to revoke this delegation.
+    pub fn revocation_digest(&self) -> Result<Vec<u8>> {
+        let mut data = REVOCATION_DOMAIN.to_vec();
+        data.extend(self.digest()?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    pub fn is_active(&self, now: u64) -> bool {
+        now <= self.expires_at
+    }
+
+    fn verify_signature(&self) -> Result<()> {
+        if verify_personal_message(&self.digest()?, &self.signature)? != self.delegator {
+            return Err(anyhow!("Delegation is not signed by its delegator"));
+        }
+        Ok(())
+    }
+}
+
+/// A member's signing key, held only while that member's keystore is unlocked.
+pub struct MemberKey {
+    pub name: String,
+    pub scheme: SignatureScheme,
+    /// 32-byte private key: a big-endian scalar for ECDSA, the seed for Ed25519.
+    key: SecretBytes,
+}
+
+impl MemberKey {
+    pub fn generate(name: &str) -> Self {
+        Self::generate_scheme(name, SignatureScheme::Secp256k1)
+    }
+
+    pub fn generate_scheme(name: &str, scheme: SignatureScheme) -> Self {
+        // Use OsRng from rand_core v0.6 which implements CryptoRngCore for k256
+        Self::generate_scheme_with_rng(name, scheme, &mut OsRng)
+    }
+
+    pub fn generate_with_rng<R: CryptoRng + RngCore>(name: &str, rng: &mut R) -> Self {
+        Self::generate_scheme_with_rng(name, SignatureScheme::Secp256k1, rng)
+    }
+
+    pub fn generate_scheme_with_rng<R: CryptoRng + RngCore>(
+        name: &str,
+        scheme: SignatureScheme,
+        rng: &mut R,
+    ) -> Self {
+        if scheme == SignatureScheme::Secp256k1 {
+            let signing_key = SigningKey::random(rng);
+            return Self {
+                name: name.to_string(),
+                scheme,
+                key: SecretBytes::new(signing_key.to_bytes().to_vec()),
+            };
+        }
+        // Any seed is an Ed25519 key; a P-256 seed is retried in the
+        // negligible case that it is zero or not below the group order.
+        loop {
+            let mut seed = vec![0u8; 32];
+            rng.fill_bytes(&mut seed);
+            if let Ok(key) = Self::from_bytes(name, scheme, &seed) {
+                return key;
+            }
+        }
+    }
+
+    /// Wraps raw 32-byte key material, e.g. decrypted from a keystore.
+    pub fn from_bytes(name: &str, scheme: SignatureScheme, bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 32 {
+            return Err(anyhow!("Private key must be 32 bytes, got {}", bytes.len()));
+        }
+        let key = Self {
             name: name.to_string(),
-            privkey_hex: hex::encode(priv_bytes),
-            pubkey_hex: hex::encode(pub_bytes),
+            scheme,
+            key: SecretBytes::new(bytes.to_vec()),
+        };
+        key.public_key()?;
+        Ok(key)
+    }
+
+    pub fn secret(&self) -> &SecretBytes {
+        &self.key
+    }
+
+    pub fn member(&self) -> Result<Member> {
+        Member::from_pubkey(&self.name, self.scheme, &hex::encode(self.public_key()?))
+    }
+
+    /// Serialized Sui personal-message signature (base64), as a Sui wallet produces.
+    pub fn sign_personal_message(&self, message: &[u8]) -> Result<String> {
+        match self.scheme {
+            SignatureScheme::Secp256k1 => sign_personal_message(&self.secp256k1()?, message),
+            SignatureScheme::Secp256r1 => {
+                let key_pair = self.secp256r1()?;
+                let signature = key_pair.sign(&personal_message_digest(message)?);
+                Ok(serialize_signature(
+                    self.scheme,
+                    signature.as_ref(),
+                    key_pair.public().as_bytes(),
+                ))
+            }
+            SignatureScheme::Ed25519 => {
+                let key_pair = self.ed25519()?;
+                let signature = key_pair.sign(&personal_message_digest(message)?);
+                Ok(serialize_signature(
+                    self.scheme,
+                    signature.as_ref(),
+                    key_pair.public().as_bytes(),
+                ))
+            }
         }
     }
 
-    pub fn sign(&self, message: &[u8]) -> Result<String> {
-        let priv_bytes = hex::decode(&self.privkey_hex)?;
-        let signing_key = SigningKey::from_bytes(priv_bytes.as_slice().into())
-            .map_err(|e| anyhow!("Invalid private key: {}", e))?;
+    /// Public key in Sui's encoding.
+    fn public_key(&self) -> Result<Vec<u8>> {
+        Ok(match self.scheme {
+            SignatureScheme::Secp256k1 => VerifyingKey::from(&self.secp256k1()?)
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            SignatureScheme::Secp256r1 => self.secp256r1()?.public().as_bytes().to_vec(),
+            SignatureScheme::Ed25519 => self.ed25519()?.public().as_bytes().to_vec(),
+        })
+    }
 
-        let signature: Signature = signing_key.sign(message);
-        Ok(hex::encode(signature.to_bytes()))
+    fn secp256k1(&self) -> Result<SigningKey> {
+        SigningKey::from_slice(self.key.expose_secret())
+            .map_err(|e| anyhow!("Invalid private key: {}", e))
+    }
+
+    fn secp256r1(&self) -> Result<Secp256r1KeyPair> {
+        Secp256r1PrivateKey::from_bytes(self.key.expose_secret())
+            .map(Secp256r1KeyPair::from)
+            .map_err(|e| anyhow!("Invalid secp256r1 private key: {}", e))
+    }
+
+    fn ed25519(&self) -> Result<Ed25519KeyPair> {
+        Ed25519PrivateKey::from_bytes(self.key.expose_secret())
+            .map(Ed25519KeyPair::from)
+            .map_err(|e| anyhow!("Invalid ed25519 private key: {}", e))
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DaoGroup {
+    /// On-disk layout version; files are read through `group_schema::parse_group`.
+    pub schema_version: u32,
     pub threshold: usize,
     pub members: Vec<Member>,
+    /// Total weight of distinct signers needed to pass a proposal. When unset, it is
+    /// `threshold`, i.e. one vote per member as long as every weight is 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum_weight: Option<u64>,
+    /// Per-role approval counts required in addition to the weight quorum,
+    /// e.g. `{"voter": 3, "guardian": 1}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub role_thresholds: BTreeMap<Role, usize>,
+    /// Set at launch, so members can sign proposals without touching the shares.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault_address: Option<String>,
+    /// TEE identity that signs the audit log, pinned at launch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tee_address: Option<String>,
+    /// Governance actions applied so far, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<GovernanceRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub delegations: Vec<Delegation>,
+    /// Ids of revoked delegations, so they cannot be registered again.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub revoked_delegations: BTreeSet<String>,
+    #[serde(default)]
+    pub governance_mode: GovernanceMode,
+    #[serde(default)]
+    pub timelock: Timelock,
 }
 
 impl DaoGroup {
+    pub fn member(&self, address: &str) -> Option<&Member> {
+        self.members.iter().find(|m| m.address == address)
+    }
+
+    pub fn member_by_name(&self, name: &str) -> Option<&Member> {
+        self.members.iter().find(|m| m.name == name)
+    }
+
+    /// Fails unless `address` belongs to a member holding `permission`.
+    pub fn require_permission(&self, address: &str, permission: Permission) -> Result<&Member> {
+        let member = self
+            .member(address)
+            .ok_or(anyhow!("{} is not a member", address))?;
+        if !member.can(permission) {
+            return Err(anyhow!(
+                "{} (roles: {}) lacks the {:?} permission",
+                member.name,
+                member
+                    .roles
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                permission
+            ));
+        }
+        Ok(member)
+    }
+
+    /// Rejects duplicate names, keys or addresses, addresses that do not match
+    /// the member's key, zero weights and a zero veto threshold.
+    pub fn validate(&self) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        let mut names = std::collections::HashSet::new();
+        let mut pubkeys = std::collections::HashSet::new();
+        for member in &self.members {
+            validate_member_name(&member.name)?;
+            if member.weight == 0 {
+                return Err(anyhow!("{} has zero voting weight", member.name));
+            }
+            if member.roles.is_empty() {
+                return Err(anyhow!("{} has no roles", member.name));
+            }
+            if Member::from_pubkey(&member.name, member.scheme, &member.pubkey_hex)?.address
+                != member.address
+            {
+                return Err(anyhow!(
+                    "Address of {} does not match its public key",
+                    member.name
+                ));
+            }
+            if !seen.insert(member.address.as_str()) {
+                return Err(anyhow!("Duplicate member address {}", member.address));
+            }
+            if !names.insert(member.name.as_str()) {
+                return Err(anyhow!("Duplicate member name '{}'", member.name));
+            }
+            if !pubkeys.insert(member.pubkey_hex.to_lowercase()) {
+                return Err(anyhow!("Duplicate member public key {}", member.pubkey_hex));
+            }
+        }
+        if self.timelock.veto_threshold == 0 {
+            return Err(anyhow!("Veto threshold must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Weight of every member allowed to vote.
+    pub fn total_weight(&self) -> u64 {
+        self.members
+            .iter()
+            .filter(|m| m.can(Permission::Vote))
+            .map(|m| m.weight)
+            .sum()
+    }
+
+    pub fn required_weight(&self) -> u64 {
+        self.quorum_weight.unwrap_or(self.threshold as u64)
+    }
+
+    /// Fails if the members together cannot reach the threshold, the quorum or a
+    /// role threshold.
+    pub fn check_quorum_reachable(&self) -> Result<()> {
+        if self.threshold == 0 || self.threshold > self.members.len() {
+            return Err(anyhow!(
+                "Threshold {} must be between 1 and the {} member(s)",
+                self.threshold,
+                self.members.len()
+            ));
+        }
+        if self.total_weight() < self.required_weight() {
+            return Err(anyhow!(
+                "Members hold {} voting weight, but the quorum is {}",
+                self.total_weight(),
+                self.required_weight()
+            ));
+        }
+        for (role, required) in &self.role_thresholds {
+            let holders = self
+                .members
+                .iter()
+                .filter(|m| m.roles.contains(role))
+                .count();
+            if holders < *required {
+                return Err(anyhow!(
+                    "{} {} approval(s) required, but only {} member(s) hold that role",
+                    required,
+                    role,
+                    holders
+                ));
+            }
+        }
+        if self.governance_mode == GovernanceMode::MultiSig {
+            MultiSigPublicKey::from_group(self)?;
+        }
+        Ok(())
+    }
+
+    /// Fingerprint of the member set, weights, roles, threshold and quorum rules,
+    /// used to detect membership changes.
+    pub fn membership_digest(&self) -> String {
+        let mut members: Vec<&Member> = self.members.iter().collect();
+        members.sort_by(|a, b| a.pubkey_hex.cmp(&b.pubkey_hex));
+
+        let mut data = (self.threshold as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(&self.required_weight().to_le_bytes());
+        for member in members {
+            data.extend_from_slice(member.pubkey_hex.as_bytes());
+            data.push(member.scheme.flag());
+            data.extend_from_slice(&member.weight.to_le_bytes());
+            data.extend(member.roles.iter().map(|r| *r as u8));
+        }
+        for (role, count) in &self.role_thresholds {
+            data.push(*role as u8);
+            data.extend_from_slice(&(*count as u64).to_le_bytes());
+        }
+        // Groups without a timelock keep the digest they had before it existed.
+        if self.timelock != Timelock::default() {
+            data.extend_from_slice(b"timelock");
+            data.extend_from_slice(&self.timelock.delay_secs.to_le_bytes());
+            data.extend_from_slice(&(self.timelock.veto_threshold as u64).to_le_bytes());
+        }
+        hex::encode(Blake2b256::digest(&data).digest)
+    }
+
+    /// Checks `signatures` (Sui address -> serialized personal-message signature)
+    /// over `message`. Each key counts once; the tally passes when the voting
+    /// members' weight reaches the quorum and every role threshold is met.
+    ///
+    /// A voter also carries the weight of members whose delegation for `kind`
+    /// is active at `now`, unless the delegator signed in person. Role
+    /// thresholds count only members who signed themselves.
     pub fn verify_proposal(
         &self,
         message: &[u8],
         signatures: &HashMap<String, String>,
-    ) -> Result<bool> {
-        let mut valid_votes = 0;
+        kind: ProposalKind,
+        now: u64,
+    ) -> VoteTally {
+        let mut tally = VoteTally {
+            required_weight: self.required_weight(),
+            ..VoteTally::default()
+        };
+        let mut counted = std::collections::HashSet::new();
+        let mut signed: Vec<&Member> = Vec::new();
+        for member in &self.members {
+            let Some(signature) = signatures.get(&member.address) else {
+                continue;
+            };
+            let signer = TalliedSigner {
+                address: member.address.clone(),
+                name: member.name.clone(),
+            };
+            if !counted.insert(member.address.as_str()) {
+                tally.duplicate_keys.push(signer);
+                continue;
+            }
+            match verify_personal_message(message, signature) {
+                Ok(address) if address == member.address => {}
+                _ => {
+                    tally.invalid_signatures.push(signer);
+                    continue;
+                }
+            }
+            signed.push(member);
+            if member.can(Permission::Vote) {
+                tally.approved_weight += member.weight;
+                tally.valid_voters.push(signer);
+            } else {
+                tally.non_voters.push(signer);
+            }
+        }
+
+        for delegation in &self.delegations {
+            if !delegation.scope.covers(kind)
+                || !delegation.is_active(now)
+                || self.check_delegation(delegation).is_err()
+            {
+                continue;
+            }
+            let Some(delegate) = tally
+                .valid_voters
+                .iter()
+                .find(|v| v.address == delegation.delegate)
+            else {
+                continue;
+            };
+            // A delegator who voted in person is already counted.
+            let delegator = self.member(&delegation.delegator).expect("checked above");
+            if tally
+                .valid_voters
+                .iter()
+                .any(|v| v.address == delegator.address)
+                || tally
+                    .delegated_votes
+                    .iter()
+                    .any(|(d, _)| d.address == delegator.address)
+            {
+                continue;
+            }
+            tally.approved_weight += delegator.weight;
+            tally.delegated_votes.push((
+                TalliedSigner {
+                    address: delegator.address.clone(),
+                    name: delegator.name.clone(),
+                },
+                delegate.clone(),
+            ));
+        }
 
-        for (member_name, sig_hex) in signatures {
-            if let Some(member) = self.members.iter().find(|m| &m.name == member_name) {
-                let pub_bytes = hex::decode(&member.pubkey_hex)?;
-                let verifying_key = VerifyingKey::from_sec1_bytes(&pub_bytes)
-                    .map_err(|e| anyhow!("Invalid pubkey for {}: {}", member_name, e))?;
+        tally.unknown_signers = signatures
+            .keys()
+            .filter(|address| self.member(address).is_none())
+            .cloned()
+            .collect();
+        tally.unknown_signers.sort();
+        tally.missing_roles = self
+            .role_thresholds
+            .iter()
+            .filter_map(|(role, required)| {
+                let approvals = signed.iter().filter(|m| m.roles.contains(role)).count();
+                (approvals < *required).then_some((*role, approvals, *required))
+            })
+            .collect();
+        tally
+    }
 
-                let sig_bytes = hex::decode(sig_hex)?;
-                let signature = Signature::from_slice(&sig_bytes)
-                    .map_err(|e| anyhow!("Invalid signature format: {}", e))?;
+    /// The message members sign to approve `action`. It covers the current
+    /// membership digest, the number of actions applied so far and the vault, so
+    /// approvals cannot be reused once anything is applied, even if a later
+    /// action restores an earlier membership.
+    pub fn governance_digest(&self, action: &GovernanceAction) -> Result<Vec<u8>> {
+        let mut data = GOVERNANCE_DOMAIN.to_vec();
+        data.extend_from_slice(self.membership_digest().as_bytes());
+        data.extend(bcs::to_bytes(&(
+            self.history.len() as u64,
+            &self.vault_address,
+            action,
+        ))?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    /// Applies `action` if the current quorum signed its governance digest and
+    /// appends it to `history`. The group is left untouched on any error.
+    pub fn apply_governance(
+        &mut self,
+        action: &GovernanceAction,
+        signatures: &HashMap<String, String>,
+        now: u64,
+    ) -> Result<()> {
+        let digest = self.governance_digest(action)?;
+        let tally = self.verify_proposal(&digest, signatures, ProposalKind::Governance, now);
+        if !tally.passed() {
+            return Err(anyhow!("Governance action rejected: {}", tally));
+        }
 
-                if verifying_key.verify(message, &signature).is_ok() {
-                    valid_votes += 1;
+        let mut next = self.clone();
+        match action {
+            GovernanceAction::SetWeight { address, weight } => {
+                next.member_mut(address)?.weight = *weight;
+            }
+            GovernanceAction::SetQuorumWeight { weight } => {
+                if *weight == 0 {
+                    return Err(anyhow!("Quorum weight must be positive"));
+                }
+                next.quorum_weight = Some(*weight);
+            }
+            GovernanceAction::SetRoles { address, roles } => {
+                next.member_mut(address)?.roles = roles.clone();
+            }
+            GovernanceAction::SetRoleThreshold { role, count } => {
+                if *count == 0 {
+                    next.role_thresholds.remove(role);
                 } else {
-                    println!("WARN: Invalid signature from {}", member_name);
+                    next.role_thresholds.insert(*role, *count);
+                }
+            }
+            GovernanceAction::RemoveMember { address } => {
+                next.member_mut(address)?;
+                next.members.retain(|m| &m.address != address);
+            }
+            GovernanceAction::RotateKey {
+                address,
+                new_pubkey_hex,
+                new_scheme,
+            } => {
+                let member = next.member_mut(address)?;
+                let rotated = Member::from_pubkey(&member.name, *new_scheme, new_pubkey_hex)?;
+                if rotated.address == *address {
+                    return Err(anyhow!("The new key is the current key"));
                 }
+                member.address = rotated.address;
+                member.pubkey_hex = rotated.pubkey_hex;
+                member.scheme = rotated.scheme;
+            }
+            GovernanceAction::SetThreshold { threshold } => {
+                next.threshold = *threshold;
+            }
+            GovernanceAction::SetTimelock { timelock } => {
+                next.timelock = *timelock;
+            }
+            GovernanceAction::AddMember { member } => {
+                next.members.push(member.clone());
             }
         }
+        next.validate()?;
+        next.check_quorum_reachable()?;
 
-        Ok(valid_votes >= self.threshold)
+        next.history.push(GovernanceRecord {
+            action: action.clone(),
+            approvers: tally.valid_voters.into_iter().map(|v| v.address).collect(),
+            membership_digest_before: self.membership_digest(),
+            membership_digest_after: next.membership_digest(),
+            applied_at: now,
+        });
+        *self = next;
+        Ok(())
+    }
+
+    /// Registers a signed delegation. A member has at most one active
+    /// delegation per scope; expired ones are dropped.
+    pub fn add_delegation(&mut self, delegation: Delegation, now: u64) -> Result<()> {
+        if self.governance_mode == GovernanceMode::MultiSig {
+            return Err(anyhow!(
+                "MultiSig governance counts members' own signatures only"
+            ));
+        }
+        self.check_delegation(&delegation)?;
+        if !delegation.is_active(now) {
+            return Err(anyhow!("Delegation expired at {}", delegation.expires_at));
+        }
+        if self.revoked_delegations.contains(&delegation.id()?) {
+            return Err(anyhow!("Delegation was revoked"));
+        }
+        self.delegations.retain(|d| d.is_active(now));
+        if let Some(existing) = self
+            .delegations
+            .iter()
+            .find(|d| d.delegator == delegation.delegator && d.scope.overlaps(delegation.scope))
+        {
+            return Err(anyhow!(
+                "{} already delegates {} votes to {}; revoke that first",
+                self.display_name(&existing.delegator),
+                existing.scope,
+                self.display_name(&existing.delegate)
+            ));
+        }
+        self.delegations.push(delegation);
+        Ok(())
+    }
+
+    /// Removes the delegation named `id`, given the delegator's signature over
+    /// its `revocation_digest()`, and keeps it from being registered again.
+    pub fn revoke_delegation(&mut self, id: &str, signature: &str) -> Result<Delegation> {
+        let index = self
+            .delegations
+            .iter()
+            .position(|d| d.id().is_ok_and(|d_id| d_id == id))
+            .ok_or(anyhow!("Delegation {} not found", id))?;
+        let delegation = &self.delegations[index];
+        if verify_personal_message(&delegation.revocation_digest()?, signature)?
+            != delegation.delegator
+        {
+            return Err(anyhow!("Revocation is not signed by the delegator"));
+        }
+        self.revoked_delegations.insert(id.to_string());
+        Ok(self.delegations.remove(index))
+    }
+
+    /// Both parties are voting members of this DAO and the delegator signed it.
+    fn check_delegation(&self, delegation: &Delegation) -> Result<()> {
+        if self.vault_address.as_deref() != Some(delegation.dao_id.as_str()) {
+            return Err(anyhow!("Delegation is for DAO {}", delegation.dao_id));
+        }
+        if delegation.delegator == delegation.delegate {
+            return Err(anyhow!("A member cannot delegate to themselves"));
+        }
+        self.require_permission(&delegation.delegator, Permission::Vote)?;
+        self.require_permission(&delegation.delegate, Permission::Vote)?;
+        delegation.verify_signature()
+    }
+
+    fn display_name<'a>(&'a self, address: &'a str) -> &'a str {
+        self.member(address).map_or(address, |m| m.name.as_str())
+    }
+
+    fn member_mut(&mut self, address: &str) -> Result<&mut Member> {
+        self.members
+            .iter_mut()
+            .find(|m| m.address == address)
+            .ok_or(anyhow!("{} is not a member", address))
+    }
+}
+
+/// A member named in a `VoteTally`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TalliedSigner {
+    pub address: String,
+    pub name: String,
+}
+
+/// Everything `DaoGroup::verify_proposal` found in a set of signatures.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VoteTally {
+    /// Valid signatures from members with the vote permission, in group order.
+    pub valid_voters: Vec<TalliedSigner>,
+    /// Valid signatures from members without the vote permission; not counted.
+    pub non_voters: Vec<TalliedSigner>,
+    /// Members whose signature does not verify under their key.
+    pub invalid_signatures: Vec<TalliedSigner>,
+    /// Signature entries whose address is not a member.
+    pub unknown_signers: Vec<String>,
+    /// Members sharing a key with one already counted; they add nothing.
+    pub duplicate_keys: Vec<TalliedSigner>,
+    /// (delegator, delegate) pairs whose delegated weight was counted.
+    pub delegated_votes: Vec<(TalliedSigner, TalliedSigner)>,
+    pub approved_weight: u64,
+    pub required_weight: u64,
+    /// Unmet role thresholds as (role, approvals, required).
+    pub missing_roles: Vec<(Role, usize, usize)>,
+}
+
+impl VoteTally {
+    pub fn passed(&self) -> bool {
+        self.approved_weight >= self.required_weight && self.missing_roles.is_empty()
+    }
+}
+
+/// E.g. "1 of 2 required weight, 0 of 1 guardian approvals; invalid signature from Bob".
+impl fmt::Display for VoteTally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} required weight",
+            self.approved_weight, self.required_weight
+        )?;
+        for (role, got, required) in &self.missing_roles {
+            write!(f, ", {} of {} {} approvals", got, required, role)?;
+        }
+        let names = |signers: &[TalliedSigner]| {
+            signers
+                .iter()
+                .map(|s| s.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        if !self.delegated_votes.is_empty() {
+            let delegated: Vec<String> = self
+                .delegated_votes
+                .iter()
+                .map(|(delegator, delegate)| format!("{} via {}", delegator.name, delegate.name))
+                .collect();
+            write!(f, "; delegated: {}", delegated.join(", "))?;
+        }
+        if !self.invalid_signatures.is_empty() {
+            write!(
+                f,
+                "; invalid signature from {}",
+                names(&self.invalid_signatures)
+            )?;
+        }
+        if !self.non_voters.is_empty() {
+            write!(
+                f,
+                "; not counted (no vote role): {}",
+                names(&self.non_voters)
+            )?;
+        }
+        if !self.duplicate_keys.is_empty() {
+            write!(f, "; duplicate key: {}", names(&self.duplicate_keys))?;
+        }
+        if !self.unknown_signers.is_empty() {
+            write!(f, "; unknown signer {}", self.unknown_signers.join(", "))?;
+        }
+        Ok(())
     }
 }
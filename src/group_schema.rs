@@ -0,0 +1,93 @@
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value, json};
+
+use crate::dao::DaoGroup;
+
+// group.json carries a `schema_version`; files written before it existed are
+// version 1. Loading runs every migration from the file's version up to
+// `GROUP_SCHEMA_VERSION`, in order, on the raw JSON. Each migration is written
+// against the layout it upgrades from, not against today's `Member` or
+// `DaoGroup`, so later changes to those types do not change how old files read.
+// Files from a newer version are refused rather than guessed at.
+
+/// The version this build reads and writes.
+pub const GROUP_SCHEMA_VERSION: u32 = 2;
+
+/// Version of group files that predate `schema_version`.
+pub const UNVERSIONED: u32 = 1;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// `MIGRATIONS[i]` upgrades version `i + 1` to `i + 2`.
+const MIGRATIONS: &[Migration] = &[v1_to_v2];
+
+/// The schema version `group` was written with.
+pub fn schema_version(group: &Value) -> Result<u32> {
+    let object = group
+        .as_object()
+        .ok_or(anyhow!("Group file is not a JSON object"))?;
+    match object.get("schema_version") {
+        None => Ok(UNVERSIONED),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= UNVERSIONED)
+            .ok_or(anyhow!("Invalid group schema_version {}", version)),
+    }
+}
+
+/// Upgrades `group` in place to `GROUP_SCHEMA_VERSION` and returns the version
+/// it started at.
+pub fn migrate(group: &mut Value) -> Result<u32> {
+    let from = schema_version(group)?;
+    if from > GROUP_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Group file has schema version {}, but this build only understands up to {}; \
+             upgrade sovereign-tee-core before using it",
+            from,
+            GROUP_SCHEMA_VERSION
+        ));
+    }
+    let object = group.as_object_mut().expect("checked by schema_version");
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip((from - 1) as usize) {
+        let to = i as u32 + 2;
+        migration(object)
+            .map_err(|e| anyhow!("Migrating group schema v{} to v{}: {}", to - 1, to, e))?;
+        object.insert("schema_version".to_string(), to.into());
+    }
+    Ok(from)
+}
+
+/// Parses a group file of any supported version.
+pub fn parse_group(content: &str) -> Result<DaoGroup> {
+    let mut group: Value = serde_json::from_str(content)?;
+    migrate(&mut group)?;
+    Ok(serde_json::from_value(group)?)
+}
+
+/// v2 writes out the member fields v1 left to serde defaults (secp256k1, weight
+/// 1, proposer/voter/executor), so changing a default later cannot silently
+/// change an existing member.
+fn v1_to_v2(group: &mut Map<String, Value>) -> Result<()> {
+    let members = group
+        .get_mut("members")
+        .and_then(Value::as_array_mut)
+        .ok_or(anyhow!("no members list"))?;
+    for member in members {
+        let member = member
+            .as_object_mut()
+            .ok_or(anyhow!("member is not an object"))?;
+        if member.contains_key("privkey_hex") || !member.contains_key("address") {
+            return Err(anyhow!(
+                "member {} uses the pre-keystore layout; run group-migrate-keys first",
+                member.get("name").and_then(Value::as_str).unwrap_or("?")
+            ));
+        }
+        member.entry("scheme").or_insert(json!("secp256k1"));
+        member.entry("weight").or_insert(json!(1));
+        member
+            .entry("roles")
+            .or_insert(json!(["proposer", "voter", "executor"]));
+    }
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use anyhow::{Result, anyhow};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand_core::{CryptoRng, OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+use crate::dao::{Member, MemberKey, validate_member_name};
+use crate::sui_utils::SignatureScheme;
+
+// One encrypted keystore file per member, so the group file only carries public keys.
+//
+// key  = Argon2id(passphrase, salt, m_cost, t_cost, p_cost)
+// blob = ChaCha20-Poly1305(key, nonce, secret key, aad = BCS(header))
+//
+// The header (name, public key, KDF parameters) is authenticated, so a keystore
+// cannot be renamed or re-pointed at another member without failing to unlock.
+// The scheme is not: a key read under another scheme derives another public
+// key, which `unlock` rejects.
+
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// Argon2id cost parameters, stored with the keystore so it can be reopened.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+    pub salt_hex: String,
+}
+
+impl KdfParams {
+    /// OWASP's Argon2id baseline (19 MiB, 2 passes): the cost used by the CLI.
+    pub const DEFAULT_M_COST: u32 = 19 * 1024;
+    pub const DEFAULT_T_COST: u32 = 2;
+    pub const DEFAULT_P_COST: u32 = 1;
+
+    fn derive_key(&self, passphrase: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        let salt = hex::decode(&self.salt_hex)?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|e| anyhow!("Invalid KDF parameters: {}", e))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase, &salt, key.as_mut())
+            .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+        Ok(key)
+    }
+}
+
+#[derive(Serialize)]
+struct KeystoreHeader<'a> {
+    version: u32,
+    name: &'a str,
+    pubkey_hex: &'a str,
+    kdf: &'a KdfParams,
+}
+
+/// A member's private key, encrypted under their passphrase.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MemberKeystore {
+    pub version: u32,
+    pub name: String,
+    pub pubkey_hex: String,
+    /// Absent in keystores written before other schemes were supported.
+    #[serde(default)]
+    pub scheme: SignatureScheme,
+    pub kdf: KdfParams,
+    pub nonce_hex: String,
+    pub ciphertext_hex: String,
+}
+
+impl MemberKeystore {
+    pub fn encrypt(key: &MemberKey, passphrase: &[u8]) -> Result<Self> {
+        Self::encrypt_with_params(
+            key,
+            passphrase,
+            KdfParams::DEFAULT_M_COST,
+            KdfParams::DEFAULT_T_COST,
+            KdfParams::DEFAULT_P_COST,
+            &mut OsRng,
+        )
+    }
+
+    pub fn encrypt_with_params<R: CryptoRng + RngCore>(
+        key: &MemberKey,
+        passphrase: &[u8],
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+        rng: &mut R,
+    ) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(anyhow!("Keystore passphrase must not be empty"));
+        }
+        let member = key.member()?;
+
+        let mut salt = [0u8; 16];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; 12];
+        rng.fill_bytes(&mut nonce);
+        let kdf = KdfParams {
+            m_cost,
+            t_cost,
+            p_cost,
+            salt_hex: hex::encode(salt),
+        };
+
+        let aad = bcs::to_bytes(&KeystoreHeader {
+            version: KEYSTORE_VERSION,
+            name: &member.name,
+            pubkey_hex: &member.pubkey_hex,
+            kdf: &kdf,
+        })?;
+        let cipher = ChaCha20Poly1305::new(&Key::from(*kdf.derive_key(passphrase)?));
+        let plaintext = Zeroizing::new(key.secret().expose_secret().to_vec());
+        let ciphertext = cipher
+            .encrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| anyhow!("Failed to encrypt keystore"))?;
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            name: member.name,
+            pubkey_hex: member.pubkey_hex,
+            scheme: member.scheme,
+            kdf,
+            nonce_hex: hex::encode(nonce),
+            ciphertext_hex: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the key. Fails on a wrong passphrase or any edit to the file.
+    pub fn unlock(&self, passphrase: &[u8]) -> Result<MemberKey> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(anyhow!("Unsupported keystore version {}", self.version));
+        }
+        let aad = bcs::to_bytes(&KeystoreHeader {
+            version: self.version,
+            name: &self.name,
+            pubkey_hex: &self.pubkey_hex,
+            kdf: &self.kdf,
+        })?;
+        let nonce: [u8; 12] = hex::decode(&self.nonce_hex)?
+            .try_into()
+            .map_err(|_| anyhow!("Invalid keystore nonce"))?;
+        let ciphertext = hex::decode(&self.ciphertext_hex)?;
+
+        let cipher = ChaCha20Poly1305::new(&Key::from(*self.kdf.derive_key(passphrase)?));
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    &Nonce::from(nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| {
+                    anyhow!("Wrong passphrase for '{}' or keystore tampered", self.name)
+                })?,
+        );
+
+        let key = MemberKey::from_bytes(&self.name, self.scheme, &plaintext)?;
+        if key.member()?.pubkey_hex != self.pubkey_hex {
+            return Err(anyhow!("Keystore key does not match its public key"));
+        }
+        Ok(key)
+    }
+
+    /// The public half, as it appears in the group file.
+    pub fn member(&self) -> Result<Member> {
+        Member::from_pubkey(&self.name, self.scheme, &self.pubkey_hex)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read keystore '{}': {}", path.display(), e))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes the keystore, refusing to overwrite an existing one.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if path.exists() {
+            return Err(anyhow!("Keystore '{}' already exists", path.display()));
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+}
+
+/// `<dir>/<name>.json`; names that could leave `dir` are rejected.
+pub fn keystore_path(dir: &str, name: &str) -> Result<PathBuf> {
+    validate_member_name(name)?;
+    Ok(Path::new(dir).join(format!("{}.json", name)))
+}
@@ -1,9 +1,22 @@
+pub mod audit_log;
+pub mod break_glass;
 pub mod dao;
+pub mod group_schema;
+pub mod keystore;
+pub mod move_model;
+pub mod multisig;
+pub mod proposal;
+pub mod proposal_store;
 pub mod pss;
+pub mod refresh_scheduler;
 pub mod scalar_utils;
+pub mod secret;
 pub mod sharding;
+pub mod share_codec;
+pub mod spending_policy;
 pub mod sui_utils;
 pub mod tee_service; // New module
+pub mod two_party;
 
 #[cfg(test)]
 mod tests;
@@ -1,16 +1,46 @@
 use anyhow::{Result, anyhow};
-use clap::{Parser, Subcommand, ValueEnum};
-use k256::Scalar;
-use k256::ecdsa::signature::Signer;
-use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
-use k256::elliptic_curve::PrimeField; // Trait required for from_repr
-use sovereign_tee_core::dao::{DaoGroup, Member};
-use sovereign_tee_core::pss::{generate_initial_shares, mock_sign_and_verify, perform_pss_refresh};
-use sovereign_tee_core::scalar_utils::bytes_to_scalar;
-use sovereign_tee_core::sharding::{recover_secret, split_secret};
-use sovereign_tee_core::sui_utils::{build_and_hash_sui_tx, pubkey_to_sui_address};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dwallet_mpc_types::dwallet_mpc::DWalletCurve;
+use k256::ecdsa::{SigningKey, VerifyingKey};
+use rand_core::{OsRng, RngCore};
+use sovereign_tee_core::audit_log::{AuditEvent, AuditHead, AuditLog, AuditReport};
+use sovereign_tee_core::break_glass::{
+    ExportBundle, ExportRequest, export_vault_key, open_export, required_approvals,
+    verify_export_approvals,
+};
+use sovereign_tee_core::dao::{
+    DEFAULT_DELEGATION_TTL_SECS, DaoGroup, Delegation, DelegationScope, GovernanceAction,
+    GovernanceMode, Member, MemberKey, Permission, ProposalKind, Role, Timelock,
+};
+use sovereign_tee_core::group_schema::{GROUP_SCHEMA_VERSION, migrate, parse_group};
+use sovereign_tee_core::keystore::{MemberKeystore, keystore_path};
+use sovereign_tee_core::multisig::{MultiSig, MultiSigPublicKey};
+use sovereign_tee_core::proposal::{
+    DEFAULT_CHAIN_ID, DEFAULT_PROPOSAL_TTL_SECS, NonceLedger, TransferProposal,
+};
+use sovereign_tee_core::proposal_store::{ExecutionRecord, ProposalStatus, ProposalStore};
+use sovereign_tee_core::pss::{
+    generate_initial_shares, mock_sign_and_verify, perform_pss_refresh, perform_sharded_refresh,
+    split_existing_key,
+};
+use sovereign_tee_core::refresh_scheduler::{RefreshPolicy, RefreshScheduler, unix_now};
+use sovereign_tee_core::scalar_utils::{bytes_to_scalar, legacy_bytes_to_scalar, scalar_to_bytes};
+use sovereign_tee_core::secret::{SecretBytes, SecretScalar};
+use sovereign_tee_core::sharding::{Shard, recover_secret_checked, split_secret};
+use sovereign_tee_core::share_codec::scalar_from_be_bytes;
+use sovereign_tee_core::spending_policy::{DAY_SECS, SpendingLedger, SpendingPolicy, WEEK_SECS};
+use sovereign_tee_core::sui_utils::{
+    SignatureScheme, decode_sui_private_key, encode_sui_private_key, parse_secp256k1_private_key,
+    pubkey_to_sui_address, verify_personal_message,
+};
+use sovereign_tee_core::two_party::{DaoSigningParty, ENCRYPTION_SEED_LEN, TeeSigningParty};
 use std::collections::HashMap;
 use std::fs;
+use std::io::{BufRead, Write};
+use zeroize::Zeroizing;
+
+/// Keystore passphrase; when unset the CLI prompts on stdin.
+const PASSPHRASE_ENV: &str = "SOVEREIGN_KEYSTORE_PASSPHRASE";
 
 #[derive(Parser)]
 #[command(name = "sovereign-cli")]
@@ -18,6 +48,40 @@ use std::fs;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    #[command(flatten)]
+    audit: AuditArgs,
+}
+
+/// The event log every command appends to, and the key that signs its entries.
+#[derive(Args)]
+struct AuditArgs {
+    /// Append-only, hash-chained audit log
+    #[arg(long, global = true, default_value = "audit.log")]
+    audit_log: String,
+    /// TEE identity key (suiprivkey1...), created owner-only at launch; guard it like the TEE
+    /// share. Every entry from launch on must be signed with it
+    #[arg(long, global = true, default_value = "tee_identity.key")]
+    tee_identity: String,
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Check the hash chain, TEE signatures and head; fails on any modification or truncation
+    Verify {
+        /// Head hash published elsewhere (e.g. on-chain); checked instead of the head file
+        #[arg(long)]
+        expected_head: Option<String>,
+        /// Number of entries the expected head covers
+        #[arg(long, requires = "expected_head")]
+        expected_len: Option<u64>,
+        /// TEE identity the log must be signed by; defaults to the group file's `tee_address`
+        #[arg(long)]
+        expected_tee: Option<String>,
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+    },
+    /// Print the log
+    Show,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -26,6 +90,106 @@ enum Strategy {
     NftSharding,
 }
 
+#[derive(Args)]
+struct LaunchArgs {
+    #[arg(long, default_value = "group.json")]
+    group_file: String,
+    #[arg(long, default_value = "dao_share.seal")]
+    dao_out: String,
+    #[arg(long, default_value = "tee_share.store")]
+    tee_out: String,
+    /// Seed of the TEE's class-groups key for two-party signing, created owner-only
+    /// unless it exists. Keep it with the TEE share
+    #[arg(long, default_value = "tee_mpc.key")]
+    tee_mpc_key: String,
+    #[arg(long, value_enum, default_value_t = Strategy::Seal)]
+    strategy: Strategy,
+    #[arg(long, default_value = "5")]
+    shards: usize,
+    /// Directory the NFT shard files (`shard_<index>.hex`) are written to
+    #[arg(long, default_value = ".")]
+    shard_dir: String,
+    #[arg(long, default_value = "refresh_state.json")]
+    refresh_state: String,
+    /// Refresh shares after this many signatures
+    #[arg(long)]
+    refresh_after_signatures: Option<u64>,
+    /// Refresh shares once this many seconds have passed
+    #[arg(long)]
+    refresh_interval_secs: Option<u64>,
+    /// Refresh shares whenever the membership changes
+    #[arg(long)]
+    refresh_on_membership_change: bool,
+    /// Launch a group that already has a vault, replacing it; recorded in the audit log
+    #[arg(long)]
+    force: bool,
+}
+
+/// Binds proposals to a chain and tracks their nonces.
+#[derive(Args)]
+struct ReplayArgs {
+    #[arg(long, default_value = DEFAULT_CHAIN_ID)]
+    chain_id: String,
+    #[arg(long, default_value = "nonces.json")]
+    nonce_ledger: String,
+}
+
+/// Spending policy checked before the TEE signs, and the history it limits.
+#[derive(Args)]
+struct PolicyArgs {
+    /// Spending policy (JSON); a missing file is an error unless --no-policy is given
+    #[arg(long, default_value = "policy.json")]
+    policy: String,
+    /// Allow every approved transfer without a policy file
+    #[arg(long)]
+    no_policy: bool,
+    #[arg(long, default_value = "spending.json")]
+    spending_ledger: String,
+}
+
+/// Exactly one membership or voting-rule change, approved and applied by the current quorum.
+#[derive(Args)]
+struct GovernanceArgs {
+    /// Member whose voting weight changes; use with --weight
+    #[arg(long, requires = "weight")]
+    set_weight: Option<String>,
+    #[arg(long)]
+    weight: Option<u64>,
+    /// New total weight needed to pass a proposal
+    #[arg(long)]
+    set_quorum_weight: Option<u64>,
+    /// Member whose roles are replaced; use with --roles
+    #[arg(long, requires = "roles")]
+    set_roles: Option<String>,
+    #[arg(long, value_delimiter = ',')]
+    roles: Option<Vec<Role>>,
+    /// Per-role approval count as ROLE=N; N = 0 removes the requirement
+    #[arg(long)]
+    set_role_threshold: Option<String>,
+    #[arg(long)]
+    remove_member: Option<String>,
+    /// Member whose key is replaced; use with --new-pubkey (see member-rotate-key)
+    #[arg(long, requires = "new_pubkey")]
+    rotate_key: Option<String>,
+    #[arg(long)]
+    new_pubkey: Option<String>,
+    /// Scheme of --new-pubkey (default: the member's current scheme)
+    #[arg(long, requires = "new_pubkey")]
+    new_scheme: Option<SignatureScheme>,
+    #[arg(long)]
+    set_threshold: Option<usize>,
+    /// Seconds a passed proposal waits for guardian vetoes before it can execute
+    #[arg(long)]
+    set_timelock: Option<u64>,
+    /// Guardian vetoes that block a proposal; use with --set-timelock (default: unchanged)
+    #[arg(long, requires = "set_timelock")]
+    veto_threshold: Option<usize>,
+    /// New member of a launched group; use with --new-pubkey (see member-create-key),
+    /// optionally --weight (default 1), --roles and --new-scheme
+    #[arg(long, requires = "new_pubkey")]
+    add_member: Option<String>,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     GenesisInit {
@@ -33,46 +197,305 @@ enum Commands {
         out: String,
         #[arg(long, default_value = "2")]
         threshold: usize,
+        /// Voting weight needed to pass a proposal (default: the threshold)
+        #[arg(long)]
+        quorum_weight: Option<u64>,
+        /// Approvals required from a role on top of the quorum, as ROLE=N (repeatable)
+        #[arg(long)]
+        role_threshold: Vec<String>,
+        /// How votes are checked: seal (TEE tally) or multisig (native Sui MultiSig)
+        #[arg(long, default_value = "seal")]
+        governance_mode: GovernanceMode,
+        /// Seconds a passed proposal waits for guardian vetoes before it can execute
+        #[arg(long, default_value = "0")]
+        timelock_secs: u64,
+        /// Guardian vetoes that block a proposal during the timelock
+        #[arg(long, default_value = "1")]
+        veto_threshold: usize,
     },
     GenesisJoin {
         #[arg(long, default_value = "group.json")]
         group_file: String,
+        /// Letters, digits, '_' and '-'; also names the member's keystore file
         #[arg(long)]
         name: String,
+        #[arg(long, default_value = "1")]
+        weight: u64,
+        /// Comma-separated: proposer, voter, executor, guardian, auditor
+        #[arg(long, value_delimiter = ',', default_value = "proposer,voter,executor")]
+        roles: Vec<Role>,
+        /// Key type: secp256k1, secp256r1 (passkey) or ed25519
+        #[arg(long, default_value = "secp256k1")]
+        scheme: SignatureScheme,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
     },
     GenesisLaunch {
+        #[command(flatten)]
+        launch: LaunchArgs,
+    },
+    /// Adopt an existing secp256k1 wallet as the vault key
+    GenesisImport {
+        #[command(flatten)]
+        launch: LaunchArgs,
+        /// File holding the private key as hex or `suiprivkey1...`
+        #[arg(long)]
+        key_file: String,
+        /// Abort unless the key controls this Sui address
+        #[arg(long)]
+        expected_address: Option<String>,
+        /// Leave the key file in place instead of overwriting and deleting it
+        #[arg(long)]
+        keep_key_file: bool,
+    },
+    GroupAddMember {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        /// Letters, digits, '_' and '-'; also names the member's keystore file
+        #[arg(long)]
+        name: String,
+        #[arg(long, default_value = "1")]
+        weight: u64,
+        /// Comma-separated: proposer, voter, executor, guardian, auditor
+        #[arg(long, value_delimiter = ',', default_value = "proposer,voter,executor")]
+        roles: Vec<Role>,
+        /// Key type: secp256k1, secp256r1 (passkey) or ed25519
+        #[arg(long, default_value = "secp256k1")]
+        scheme: SignatureScheme,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+    },
+    /// Sign a voting-rule change as a member
+    GovernanceApprove {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[arg(long)]
+        member: String,
+        #[command(flatten)]
+        action: GovernanceArgs,
+        #[arg(long, default_value = "governance_approvals.json")]
+        approvals: String,
+    },
+    /// Apply a voting-rule change once the current quorum has approved it
+    GovernanceApply {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        /// Where a rotated member's pending keystore is activated
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[command(flatten)]
+        action: GovernanceArgs,
+        #[arg(long, default_value = "governance_approvals.json")]
+        approvals: String,
+    },
+    /// Generate a replacement key for a member, pending governance approval
+    MemberRotateKey {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[arg(long)]
+        member: String,
+        /// Key type of the new key (default: the member's current scheme)
+        #[arg(long)]
+        scheme: Option<SignatureScheme>,
+    },
+    /// Generate a keystore for a new member of a launched group, pending governance approval
+    MemberCreateKey {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        /// Letters, digits, '_' and '-'; also names the member's keystore file
+        #[arg(long)]
+        name: String,
+        /// Key type: secp256k1, secp256r1 (passkey) or ed25519
+        #[arg(long, default_value = "secp256k1")]
+        scheme: SignatureScheme,
+    },
+    /// Print the applied governance actions
+    GroupHistory {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+    },
+    /// Print the Sui MultiSig public key and address for the voting members
+    GroupMultisig {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+    },
+    /// Delegate a member's voting weight to another member for a while
+    DelegationCreate {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[arg(long)]
+        member: String,
+        /// Name of the member who votes on the delegator's behalf
+        #[arg(long)]
+        to: String,
+        /// all, transfers or governance
+        #[arg(long, default_value = "all")]
+        scope: DelegationScope,
+        #[arg(long, default_value_t = DEFAULT_DELEGATION_TTL_SECS)]
+        expires_in: u64,
+    },
+    /// Revoke one of the member's delegations (see delegation-list for ids)
+    DelegationRevoke {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[arg(long)]
+        member: String,
+        #[arg(long)]
+        id: String,
+    },
+    DelegationList {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+    },
+    /// Upgrade a group file to the current schema version, keeping the original
+    /// next to it as `<file>.v<N>.bak`
+    GroupMigrate {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Upgrade a legacy group file: move private keys into per-member keystores
+    /// and add each member's Sui address
+    GroupMigrateKeys {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+    },
+    /// Re-encode share files written before the canonical share codec, keeping
+    /// the vault address they control, and record that address in the group
+    ShareMigrate {
         #[arg(long, default_value = "group.json")]
         group_file: String,
         #[arg(long, default_value = "dao_share.seal")]
-        dao_out: String,
+        dao_in: String,
         #[arg(long, default_value = "tee_share.store")]
-        tee_out: String,
+        tee_in: String,
         #[arg(long, value_enum, default_value_t = Strategy::Seal)]
         strategy: Strategy,
-        #[arg(long, default_value = "5")]
-        shards: usize,
+        #[arg(long, value_delimiter = ' ', num_args = 1..)]
+        shards_in: Option<Vec<String>>,
     },
-    GroupAddMember {
+    /// Draft a transfer as a member with the proposer role
+    ProposalCreate {
         #[arg(long, default_value = "group.json")]
         group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
         #[arg(long)]
-        name: String,
+        member: String,
+        #[arg(long)]
+        recipient: String,
+        #[arg(long)]
+        amount: u64,
+        /// Seconds members have to vote and execute
+        #[arg(long, default_value_t = DEFAULT_PROPOSAL_TTL_SECS)]
+        expires_in: u64,
+        #[command(flatten)]
+        replay: ReplayArgs,
+        #[arg(long, default_value = "proposals.json")]
+        store: String,
+    },
+    /// Unlock one member's keystore and vote on an active proposal
+    ProposalVote {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[arg(long)]
+        member: String,
+        /// Proposal id, as printed by proposal-create
+        #[arg(long)]
+        id: u64,
+        #[command(flatten)]
+        replay: ReplayArgs,
+        #[arg(long, default_value = "proposals.json")]
+        store: String,
+    },
+    /// Veto a passed proposal during its timelock (guardians only)
+    ProposalVeto {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[arg(long)]
+        member: String,
+        #[arg(long)]
+        id: u64,
+        #[command(flatten)]
+        replay: ReplayArgs,
+        #[arg(long, default_value = "proposals.json")]
+        store: String,
+    },
+    /// List stored proposals, optionally by status (active, passed, failed, executed or 0-3)
+    ProposalList {
+        #[arg(long)]
+        status: Option<ProposalStatus>,
+        #[arg(long, default_value = "nonces.json")]
+        nonce_ledger: String,
+        #[arg(long, default_value = "proposals.json")]
+        store: String,
+    },
+    /// Print one proposal with its votes and execution record
+    ProposalShow {
+        #[arg(long)]
+        id: u64,
+        #[arg(long, default_value = "nonces.json")]
+        nonce_ledger: String,
+        #[arg(long, default_value = "proposals.json")]
+        store: String,
     },
     ProposalExecute {
         #[arg(long, default_value = "group.json")]
         group_file: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        /// Member with the executor role running the signing step
+        #[arg(long)]
+        executor: String,
         #[arg(long, default_value = "dao_share.seal")]
         dao_in: String,
         #[arg(long, default_value = "tee_share.store")]
         tee_in: String,
+        /// Seed of the TEE's class-groups key for two-party signing, as created by launch
+        #[arg(long, default_value = "tee_mpc.key")]
+        tee_mpc_key: String,
         #[arg(long)]
-        recipient: String,
-        #[arg(long)]
-        amount: u64,
+        id: u64,
+        #[command(flatten)]
+        replay: ReplayArgs,
         #[arg(long, value_enum, default_value_t = Strategy::Seal)]
         strategy: Strategy,
         #[arg(long, value_delimiter = ' ', num_args = 1..)]
         shards_in: Option<Vec<String>>,
+        #[arg(long, default_value = "refresh_state.json")]
+        refresh_state: String,
+        #[arg(long, default_value = "proposals.json")]
+        store: String,
+        #[command(flatten)]
+        spending: PolicyArgs,
+    },
+    /// Inspect or verify the audit log
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+    /// Print the spending policy and what the rolling limits leave
+    PolicyStatus {
+        #[command(flatten)]
+        spending: PolicyArgs,
     },
     GroupRefresh {
         #[arg(long, default_value = "group.json")]
@@ -85,196 +508,2199 @@ enum Commands {
         dao_out: String,
         #[arg(long, default_value = "tee_share_new.store")]
         tee_out: String,
+        #[arg(long, value_enum, default_value_t = Strategy::Seal)]
+        strategy: Strategy,
+        /// Every outstanding shard file; all of them are replaced
+        #[arg(long, value_delimiter = ' ', num_args = 1..)]
+        shards_in: Option<Vec<String>>,
+        #[arg(long, default_value = "5")]
+        shards: usize,
+        /// Directory the refreshed shard files are written to
+        #[arg(long, default_value = ".")]
+        shard_dir: String,
+        #[arg(long, default_value = "refresh_state.json")]
+        refresh_state: String,
+    },
+    RefreshStatus {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "refresh_state.json")]
+        refresh_state: String,
+    },
+    /// Draft a dated request to export the vault key to a recovery key
+    BreakGlassRequest {
+        #[arg(long)]
+        vault_address: String,
+        /// Compressed secp256k1 public key (hex) the key will be encrypted to
+        #[arg(long)]
+        recovery_pubkey: String,
+        #[arg(long)]
+        reason: String,
+        #[arg(long, default_value = "86400")]
+        valid_for_secs: u64,
+        #[arg(long, default_value = "export_request.json")]
+        out: String,
+    },
+    /// Sign an export request as a member
+    BreakGlassApprove {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "export_request.json")]
+        request: String,
+        #[arg(long)]
+        member: String,
+        #[arg(long, default_value = "keystore")]
+        keystore_dir: String,
+        #[arg(long, default_value = "export_approvals.json")]
+        approvals: String,
+    },
+    /// Export the vault key once a super-majority has approved
+    BreakGlassExport {
+        #[arg(long, default_value = "group.json")]
+        group_file: String,
+        #[arg(long, default_value = "export_request.json")]
+        request: String,
+        #[arg(long, default_value = "export_approvals.json")]
+        approvals: String,
+        #[arg(long, default_value = "dao_share.seal")]
+        dao_in: String,
+        #[arg(long, default_value = "tee_share.store")]
+        tee_in: String,
+        #[arg(long, value_enum, default_value_t = Strategy::Seal)]
+        strategy: Strategy,
+        #[arg(long, value_delimiter = ' ', num_args = 1..)]
+        shards_in: Option<Vec<String>>,
+        #[arg(long, default_value = "export_bundle.json")]
+        out: String,
+        #[arg(long, default_value = "break_glass_record.json")]
+        record_out: String,
+    },
+    /// Decrypt an export bundle with the recovery private key
+    BreakGlassOpen {
+        #[arg(long, default_value = "export_bundle.json")]
+        bundle: String,
+        /// File holding the recovery private key as hex or `suiprivkey1...`
+        #[arg(long)]
+        recovery_key_file: String,
+        /// Where to write the vault key as `suiprivkey1...`
+        #[arg(long, default_value = "vault.key")]
+        out: String,
     },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    let audit_args = &cli.audit;
 
     match cli.command {
-        Commands::GenesisInit { out, threshold } => {
+        Commands::GenesisInit {
+            out,
+            threshold,
+            quorum_weight,
+            role_threshold,
+            governance_mode,
+            timelock_secs,
+            veto_threshold,
+        } => {
+            if quorum_weight == Some(0) {
+                return Err(anyhow!("Quorum weight must be positive"));
+            }
             let group = DaoGroup {
+                schema_version: GROUP_SCHEMA_VERSION,
                 threshold,
                 members: Vec::new(),
+                quorum_weight,
+                role_thresholds: role_threshold
+                    .iter()
+                    .map(|spec| parse_role_threshold(spec))
+                    .collect::<Result<_>>()?,
+                vault_address: None,
+                tee_address: None,
+                history: Vec::new(),
+                delegations: Vec::new(),
+                revoked_delegations: Default::default(),
+                governance_mode,
+                timelock: Timelock {
+                    delay_secs: timelock_secs,
+                    veto_threshold,
+                },
             };
+            group.validate()?;
             let json = serde_json::to_string_pretty(&group)?;
             fs::write(&out, json)?;
+            audit(
+                audit_args,
+                AuditEvent::GenesisInit {
+                    threshold: threshold as u64,
+                    quorum_weight: group.required_weight(),
+                    membership_digest: group.membership_digest(),
+                },
+            )?;
             println!(
-                "Genesis: Initialized empty group at '{}' with threshold {}",
-                out, threshold
+                "Genesis: Initialized empty group at '{}' with threshold {} (quorum weight {}, {} governance)",
+                out,
+                threshold,
+                group.required_weight(),
+                group.governance_mode
             );
         }
 
-        Commands::GenesisJoin { group_file, name } => {
-            let content = fs::read_to_string(&group_file)?;
-            let mut group: DaoGroup = serde_json::from_str(&content)?;
-            if group.members.iter().any(|m| m.name == name) {
-                return Err(anyhow!("Member '{}' already exists", name));
-            }
-            let member = Member::new(&name);
+        Commands::GenesisJoin {
+            group_file,
+            name,
+            weight,
+            roles,
+            scheme,
+            keystore_dir,
+        } => {
+            let mut group = load_group(&group_file)?;
+            let member =
+                create_member_keystore(&group, &name, weight, &roles, scheme, &keystore_dir)?;
             println!(
-                "Member Joined: {} (PubKey: {})",
-                member.name, member.pubkey_hex
+                "Member Joined: {} (Address: {}, PubKey: {} {}, Weight: {}, Roles: {})",
+                member.name,
+                member.address,
+                member.scheme,
+                member.pubkey_hex,
+                member.weight,
+                format_roles(&member)
             );
+            audit(audit_args, member_joined(&member))?;
             group.members.push(member);
             fs::write(&group_file, serde_json::to_string_pretty(&group)?)?;
             println!("Group updated. Total members: {}", group.members.len());
         }
 
-        Commands::GenesisLaunch {
-            group_file,
-            dao_out,
-            tee_out,
-            strategy,
-            shards,
-        } => {
-            let content = fs::read_to_string(&group_file)?;
-            let group: DaoGroup = serde_json::from_str(&content)?;
-            if group.members.len() < group.threshold {
-                return Err(anyhow!("Not enough members to launch!"));
-            }
+        Commands::GenesisLaunch { launch } => {
+            let mut group = load_launch_group(&launch.group_file)?;
 
             println!("--- Launch Sequence Initiated ---");
             let (s_dao, s_tee) = generate_initial_shares()?;
-            let (_, pubkey_hex) = mock_sign_and_verify(&s_dao, &s_tee, b"init")?;
-            let pubkey_bytes = hex::decode(&pubkey_hex)?;
-            let verifying_key = VerifyingKey::from_sec1_bytes(&pubkey_bytes)?;
-            let sui_addr = pubkey_to_sui_address(&verifying_key);
-
-            println!("3. DAO Sui Address Generated: {}", sui_addr);
+            launch_vault(&launch, audit_args, &mut group, &s_dao, &s_tee, None)?;
+            println!("--- Launch Complete: Sovereign DAO is Live ---");
+        }
 
-            match strategy {
-                Strategy::Seal => {
-                    println!("4. Sealing DAO Share to Walrus...");
-                    fs::write(&dao_out, hex::encode(&s_dao))?;
-                }
-                Strategy::NftSharding => {
-                    println!(
-                        "4. Sharding DAO Share into {} NFT Blobs (Threshold: {})...",
-                        shards, group.threshold
-                    );
-                    let s_dao_scalar = bytes_to_scalar(&s_dao)?;
-                    let shares = split_secret(&s_dao_scalar, group.threshold, shards);
+        Commands::GenesisImport {
+            launch,
+            key_file,
+            expected_address,
+            keep_key_file,
+        } => {
+            let mut group = load_launch_group(&launch.group_file)?;
 
-                    for (idx, share) in shares {
-                        let filename = format!("shard_{}.hex", idx);
-                        fs::write(&filename, hex::encode(share.to_bytes()))?;
-                        println!("   -> Minted NFT #{} linked to {}", idx, filename);
-                    }
+            println!("--- Wallet Import Initiated ---");
+            let key = {
+                let text = Zeroizing::new(
+                    fs::read_to_string(&key_file)
+                        .map_err(|e| anyhow!("Failed to read '{}': {}", key_file, e))?,
+                );
+                parse_secp256k1_private_key(&text)?
+            };
+            let original = key_address(&key)?;
+            if let Some(expected) = &expected_address {
+                if !expected.eq_ignore_ascii_case(&original) {
+                    return Err(anyhow!(
+                        "Key controls {}, not the expected address {}",
+                        original,
+                        expected
+                    ));
                 }
             }
+            println!("1. Existing wallet: {}", original);
 
-            println!("5. Storing TEE Share...");
-            fs::write(&tee_out, hex::encode(&s_tee))?;
-            println!("--- Launch Complete: Sovereign DAO is Live ---");
+            let (s_dao, s_tee) = split_existing_key(&key)?;
+            drop(key);
+            println!("2. Split key into DAO and TEE shares");
+            launch_vault(
+                &launch,
+                audit_args,
+                &mut group,
+                &s_dao,
+                &s_tee,
+                Some(&original),
+            )?;
+
+            if keep_key_file {
+                println!("WARN: Key file {} was kept. Delete it securely.", key_file);
+            } else {
+                shred_file(&key_file)?;
+                println!("7. Overwrote and removed {}", key_file);
+            }
+            println!(
+                "--- Import Complete: {} is now a Sovereign Vault ---",
+                original
+            );
         }
 
-        Commands::GroupAddMember { group_file, name } => {
-            let content = fs::read_to_string(&group_file)?;
-            let mut group: DaoGroup = serde_json::from_str(&content)?;
-            let member = Member::new(&name);
+        Commands::GroupAddMember {
+            group_file,
+            name,
+            weight,
+            roles,
+            scheme,
+            keystore_dir,
+        } => {
+            let mut group = load_group(&group_file)?;
+            let member =
+                create_member_keystore(&group, &name, weight, &roles, scheme, &keystore_dir)?;
+            println!(
+                "Added member {} with weight {} (Roles: {})",
+                name,
+                weight,
+                format_roles(&member)
+            );
+            audit(audit_args, member_joined(&member))?;
             group.members.push(member);
             fs::write(&group_file, serde_json::to_string_pretty(&group)?)?;
-            println!("Added member {}", name);
         }
 
-        Commands::ProposalExecute {
+        Commands::GovernanceApprove {
             group_file,
-            dao_in,
-            tee_in,
-            recipient,
-            amount,
-            strategy,
-            shards_in,
+            keystore_dir,
+            member,
+            action,
+            approvals,
         } => {
-            let content = fs::read_to_string(&group_file)?;
-            let group: DaoGroup = serde_json::from_str(&content)?;
+            let group = load_group(&group_file)?;
+            let action = governance_action(&group, &action)?;
+            let signer = unlock_member(&group, &keystore_dir, &member)?;
+            group.require_permission(&signer.member()?.address, Permission::Vote)?;
 
-            // 1. Load s_tee as Scalar
-            let s_tee_bytes = hex::decode(fs::read_to_string(&tee_in)?.trim())?;
-            let s_tee_scalar = bytes_to_scalar(&s_tee_bytes)?;
+            let mut signatures: HashMap<String, String> = match fs::read_to_string(&approvals) {
+                Ok(content) => serde_json::from_str(&content)?,
+                Err(_) => HashMap::new(),
+            };
+            signatures.insert(
+                signer.member()?.address,
+                signer.sign_personal_message(&group.governance_digest(&action)?)?,
+            );
+            fs::write(&approvals, serde_json::to_string_pretty(&signatures)?)?;
+            println!(
+                "{} approved {:?} ({})",
+                member,
+                action,
+                group.verify_proposal(
+                    &group.governance_digest(&action)?,
+                    &signatures,
+                    ProposalKind::Governance,
+                    unix_now()?
+                )
+            );
+        }
 
-            // 2. Load s_dao as Scalar
-            let s_dao_scalar = match strategy {
-                Strategy::Seal => {
-                    let bytes = hex::decode(fs::read_to_string(&dao_in)?.trim())?;
-                    bytes_to_scalar(&bytes)?
+        Commands::GovernanceApply {
+            group_file,
+            keystore_dir,
+            action,
+            approvals,
+        } => {
+            let mut group = load_group(&group_file)?;
+            let action = governance_action(&group, &action)?;
+            let signatures: HashMap<String, String> = serde_json::from_str(
+                &fs::read_to_string(&approvals)
+                    .map_err(|e| anyhow!("Failed to read approvals '{}': {}", approvals, e))?,
+            )?;
+            group.apply_governance(&action, &signatures, unix_now()?)?;
+            fs::write(&group_file, serde_json::to_string_pretty(&group)?)?;
+            // The approvals are spent; the digest has moved on anyway.
+            fs::remove_file(&approvals)?;
+            let record = group.history.last().expect("just applied");
+            audit(
+                audit_args,
+                AuditEvent::GovernanceApplied {
+                    action: action.clone(),
+                    approvers: record.approvers.clone(),
+                    membership_digest: record.membership_digest_after.clone(),
+                },
+            )?;
+            println!(
+                "Applied {:?}. {} member(s), threshold {}, total weight {}, quorum {}.",
+                action,
+                group.members.len(),
+                group.threshold,
+                group.total_weight(),
+                group.required_weight()
+            );
+
+            match &action {
+                GovernanceAction::RotateKey { new_pubkey_hex, .. } => {
+                    activate_rotated_keystore(&group, &keystore_dir, new_pubkey_hex)?
                 }
-                Strategy::NftSharding => {
-                    let files =
-                        shards_in.ok_or(anyhow!("Strategy NftSharding requires --shards-in"))?;
-                    if files.len() < group.threshold {
-                        return Err(anyhow!(
-                            "Not enough shards! Need {}, got {}",
-                            group.threshold,
-                            files.len()
-                        ));
-                    }
-                    println!("[TEE] Collecting shards from NFT holders...");
-                    let mut shares = Vec::new();
-                    for file in files {
-                        let bytes = hex::decode(fs::read_to_string(&file)?.trim())?;
-                        let idx_str = file.replace("shard_", "").replace(".hex", "");
-                        let idx: usize = idx_str.parse()?;
-
-                        let scalar_opt = Scalar::from_repr(*k256::FieldBytes::from_slice(&bytes));
-                        if scalar_opt.is_none().into() {
-                            return Err(anyhow!("Invalid scalar in shard {}", file));
-                        }
-                        shares.push((idx, scalar_opt.unwrap()));
-                        println!("   -> Loaded shard from {}", file);
-                    }
-                    println!("[TEE] Interpolating Secret from {} shards...", shares.len());
-                    recover_secret(&shares)?
+                GovernanceAction::AddMember { member } => {
+                    activate_rotated_keystore(&group, &keystore_dir, &member.pubkey_hex)?;
+                    println!(
+                        "With NFT sharding, run group-refresh with one more shard to give {} a shard.",
+                        member.name
+                    )
                 }
-            };
-
-            // 3. Reconstruct Private Key & Sign
-            let sk = s_dao_scalar + s_tee_scalar;
-            let signing_key = SigningKey::from_bytes(&sk.to_bytes())?;
-            let verifying_key = VerifyingKey::from(&signing_key);
-            let sender = pubkey_to_sui_address(&verifying_key);
+                GovernanceAction::RemoveMember { .. } | GovernanceAction::SetThreshold { .. } => {
+                    println!(
+                        "With NFT sharding, run group-refresh with all shards so the old shards stop working."
+                    )
+                }
+                _ => {}
+            }
+        }
 
+        Commands::MemberRotateKey {
+            group_file,
+            keystore_dir,
+            member,
+            scheme,
+        } => {
+            let group = load_group(&group_file)?;
+            let current = group
+                .member_by_name(&member)
+                .ok_or(anyhow!("Member '{}' not found", member))?;
+            let passphrase = read_passphrase(&format!("New passphrase for {}", member))?;
+            let key = MemberKey::generate_scheme(&member, scheme.unwrap_or(current.scheme));
+            let rotated = key.member()?;
+            let path = pending_keystore_path(&keystore_dir, &member)?;
+            MemberKeystore::encrypt(&key, passphrase.as_bytes())?.save(&path)?;
+            println!("Pending keystore written to {}", path.display());
             println!(
-                "--- Proposal: Transfer {} MIST to {} ---",
-                amount, recipient
+                "Approve with: governance-approve --rotate-key {} --new-pubkey {} --new-scheme {}",
+                member, rotated.pubkey_hex, rotated.scheme
             );
-            println!("Sender (DAO Vault): {}", sender);
-
-            let tx_hash = build_and_hash_sui_tx(&sender, &recipient, amount)?;
-            println!("Transaction Digest: {}", hex::encode(&tx_hash));
+            println!(
+                "{} stays {} until the rotation is applied.",
+                member, current.address
+            );
+        }
 
-            if let Strategy::Seal = strategy {
-                let mut signatures = HashMap::new();
-                println!("Collecting Votes...");
-                for i in 0..group.threshold {
-                    if i >= group.members.len() {
-                        break;
-                    }
-                    let member = &group.members[i];
-                    let sig = member.sign(&tx_hash)?;
-                    signatures.insert(member.name.clone(), sig);
-                }
+        Commands::MemberCreateKey {
+            group_file,
+            keystore_dir,
+            name,
+            scheme,
+        } => {
+            let group = load_group(&group_file)?;
+            if group.member_by_name(&name).is_some() {
+                return Err(anyhow!("Member '{}' already exists", name));
+            }
+            let path = pending_keystore_path(&keystore_dir, &name)?;
+            let passphrase = read_passphrase(&format!("New passphrase for {}", name))?;
+            let key = MemberKey::generate_scheme(&name, scheme);
+            let member = key.member()?;
+            MemberKeystore::encrypt(&key, passphrase.as_bytes())?.save(&path)?;
+            println!("Pending keystore written to {}", path.display());
+            println!(
+                "Approve with: governance-approve --add-member {} --new-pubkey {} --new-scheme {}",
+                name, member.pubkey_hex, member.scheme
+            );
+        }
 
-                println!("\n[Seal Smart Contract] Verifying signatures...");
-                if !group.verify_proposal(&tx_hash, &signatures)? {
-                    return Err(anyhow!("Proposal Rejected."));
-                }
-                println!("[Seal Smart Contract] Access Granted.");
+        Commands::GroupHistory { group_file } => {
+            let group = load_group(&group_file)?;
+            if group.history.is_empty() {
+                println!("No governance changes recorded.");
             }
+            for (i, record) in group.history.iter().enumerate() {
+                println!(
+                    "#{} at {}: {:?} ({} approver(s); membership {} -> {})",
+                    i + 1,
+                    record.applied_at,
+                    record.action,
+                    record.approvers.len(),
+                    short_hex(&record.membership_digest_before),
+                    short_hex(&record.membership_digest_after)
+                );
+            }
+        }
 
-            println!("\n[TEE] Signing transaction digest...");
-            let signature: Signature = signing_key.sign(&tx_hash);
+        Commands::GroupMultisig { group_file } => {
+            let group = load_group(&group_file)?;
+            let multisig_pk = MultiSigPublicKey::from_group(&group)?;
+            println!("Governance mode: {}", group.governance_mode);
+            for member in &multisig_pk.pk_map {
+                println!(
+                    "  {} ({}) weight {}",
+                    member.address(),
+                    member.scheme,
+                    member.weight
+                );
+            }
+            println!("Threshold: {}", multisig_pk.threshold);
+            println!("MultiSig address: {}", multisig_pk.address());
+        }
 
-            println!("--- Execution Successful ---");
-            println!("Signature: {}", hex::encode(signature.to_bytes()));
-            println!("Status: VALID SIGNATURE FOR SUI NETWORK");
+        Commands::DelegationCreate {
+            group_file,
+            keystore_dir,
+            member,
+            to,
+            scope,
+            expires_in,
+        } => {
+            let mut group = load_group(&group_file)?;
+            let key = unlock_member(&group, &keystore_dir, &member)?;
+            let delegate = group
+                .member_by_name(&to)
+                .ok_or(anyhow!("Member '{}' not found", to))?
+                .address
+                .clone();
+            let now = unix_now()?;
+            let delegation = Delegation::create(
+                &group,
+                &key,
+                &delegate,
+                scope,
+                now.saturating_add(expires_in),
+            )?;
+            let id = delegation.id()?;
+            let event = AuditEvent::DelegationCreated {
+                id: id.clone(),
+                delegator: delegation.delegator.clone(),
+                delegate: delegation.delegate.clone(),
+                scope,
+                expires_at: delegation.expires_at,
+            };
+            group.add_delegation(delegation, now)?;
+            fs::write(&group_file, serde_json::to_string_pretty(&group)?)?;
+            audit(audit_args, event)?;
+            println!(
+                "{} delegated {} votes to {} for {}s (id {})",
+                member, scope, to, expires_in, id
+            );
         }
 
-        Commands::GroupRefresh { .. } => {
-            println!("Refresh not fully adapted for NftSharding in this demo.");
+        Commands::DelegationRevoke {
+            group_file,
+            keystore_dir,
+            member,
+            id,
+        } => {
+            let mut group = load_group(&group_file)?;
+            let key = unlock_member(&group, &keystore_dir, &member)?;
+            let delegation = group
+                .delegations
+                .iter()
+                .find(|d| d.id().is_ok_and(|d_id| d_id == id))
+                .ok_or(anyhow!("Delegation {} not found", id))?;
+            let signature = key.sign_personal_message(&delegation.revocation_digest()?)?;
+            group.revoke_delegation(&id, &signature)?;
+            fs::write(&group_file, serde_json::to_string_pretty(&group)?)?;
+            audit(audit_args, AuditEvent::DelegationRevoked { id: id.clone() })?;
+            println!("{} revoked delegation {}", member, id);
         }
-    }
 
+        Commands::DelegationList { group_file } => {
+            let group = load_group(&group_file)?;
+            if group.delegations.is_empty() {
+                println!("No delegations.");
+            }
+            let now = unix_now()?;
+            let name = |address: &str| {
+                group
+                    .member(address)
+                    .map_or(address.to_string(), |m| m.name.clone())
+            };
+            for delegation in &group.delegations {
+                println!(
+                    "{} {} -> {} ({}) until {}{}",
+                    delegation.id()?,
+                    name(&delegation.delegator),
+                    name(&delegation.delegate),
+                    delegation.scope,
+                    delegation.expires_at,
+                    if delegation.is_active(now) {
+                        ""
+                    } else {
+                        " [expired]"
+                    }
+                );
+            }
+        }
+
+        Commands::GroupMigrate {
+            group_file,
+            dry_run,
+        } => {
+            let content = fs::read_to_string(&group_file)?;
+            let mut value: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Invalid group file '{}': {}", group_file, e))?;
+            let from = migrate(&mut value)?;
+            let group: DaoGroup = serde_json::from_value(value)?;
+            group.validate()?;
+            if from == GROUP_SCHEMA_VERSION {
+                println!(
+                    "{} is already at schema version {}",
+                    group_file, GROUP_SCHEMA_VERSION
+                );
+            } else if dry_run {
+                println!(
+                    "{} would be migrated from schema version {} to {}",
+                    group_file, from, GROUP_SCHEMA_VERSION
+                );
+            } else {
+                let backup = format!("{}.v{}.bak", group_file, from);
+                fs::write(&backup, &content)?;
+                fs::write(&group_file, serde_json::to_string_pretty(&group)?)?;
+                println!(
+                    "Migrated {} from schema version {} to {} (original kept in {})",
+                    group_file, from, GROUP_SCHEMA_VERSION, backup
+                );
+            }
+        }
+
+        Commands::ShareMigrate {
+            group_file,
+            dao_in,
+            tee_in,
+            strategy,
+            shards_in,
+        } => {
+            let mut group = load_group(&group_file)?;
+            // Launches since the share codec record the vault; only older groups lack it.
+            if let Some(vault) = &group.vault_address {
+                return Err(anyhow!(
+                    "{} already records vault {}; its shares use the current encoding",
+                    group_file,
+                    vault
+                ));
+            }
+            let s_tee = legacy_bytes_to_scalar(read_secret_hex(&tee_in)?.expose_secret())
+                .map_err(|e| anyhow!("Reading {}: {}", tee_in, e))?;
+            // Shards were always plain big-endian scalars, so only the additive shares change.
+            let s_dao = match strategy {
+                Strategy::Seal => legacy_bytes_to_scalar(read_secret_hex(&dao_in)?.expose_secret())
+                    .map_err(|e| anyhow!("Reading {}: {}", dao_in, e))?,
+                Strategy::NftSharding => {
+                    load_dao_scalar(&strategy, &dao_in, shards_in, group.threshold)?
+                }
+            };
+            let address = vault_address(&s_dao, &s_tee)?;
+            group.vault_address = Some(address.clone());
+
+            let group_json = serde_json::to_string_pretty(&group)?;
+            let dao_hex = secret_hex(&scalar_to_bytes(s_dao.expose_secret())?);
+            let tee_hex = secret_hex(&scalar_to_bytes(s_tee.expose_secret())?);
+            let mut files = vec![(group_file.as_str(), group_json.as_bytes())];
+            if let Strategy::Seal = strategy {
+                files.push((dao_in.as_str(), dao_hex.as_bytes()));
+            }
+            files.push((tee_in.as_str(), tee_hex.as_bytes()));
+            replace_files(&files)?;
+            println!("Re-encoded shares for vault {}", address);
+        }
+
+        Commands::GroupMigrateKeys {
+            group_file,
+            keystore_dir,
+        } => {
+            let content = Zeroizing::new(fs::read_to_string(&group_file)?);
+            let mut legacy: serde_json::Value = serde_json::from_str(&content)?;
+            let members = legacy["members"]
+                .as_array_mut()
+                .ok_or(anyhow!("'{}' has no members list", group_file))?;
+
+            let mut passphrase = None;
+            let mut keystores = Vec::new();
+            for entry in members.iter_mut() {
+                let Some(object) = entry.as_object_mut() else {
+                    continue;
+                };
+                let name = object["name"]
+                    .as_str()
+                    .ok_or(anyhow!("Member without a name"))?
+                    .to_string();
+                let pubkey_hex = object["pubkey_hex"]
+                    .as_str()
+                    .ok_or(anyhow!("Member {} has no pubkey_hex", name))?;
+                // Legacy group files only ever held secp256k1 keys.
+                let address =
+                    Member::from_pubkey(&name, SignatureScheme::Secp256k1, pubkey_hex)?.address;
+                object.insert("address".to_string(), address.into());
+
+                let Some(privkey) = object.remove("privkey_hex") else {
+                    continue;
+                };
+                if passphrase.is_none() {
+                    passphrase = Some(read_passphrase("New keystore passphrase")?);
+                }
+                let passphrase = passphrase.as_ref().expect("set above");
+                let privkey = Zeroizing::new(
+                    privkey
+                        .as_str()
+                        .ok_or(anyhow!("Invalid privkey_hex for {}", name))?
+                        .to_string(),
+                );
+                let key = MemberKey::from_bytes(
+                    &name,
+                    SignatureScheme::Secp256k1,
+                    &Zeroizing::new(hex::decode(&*privkey)?),
+                )?;
+                if Some(key.member()?.pubkey_hex.as_str()) != object["pubkey_hex"].as_str() {
+                    return Err(anyhow!("Private key of {} does not match its pubkey", name));
+                }
+                let path = keystore_path(&keystore_dir, &name)?;
+                keystores.push((
+                    name,
+                    path,
+                    MemberKeystore::encrypt(&key, passphrase.as_bytes())?,
+                ));
+            }
+
+            // Nothing is written until the migrated group is known to be valid.
+            let from = migrate(&mut legacy)?;
+            let group: DaoGroup = serde_json::from_value(legacy)?;
+            group.validate()?;
+            for (name, path, keystore) in &keystores {
+                keystore.save(path)?;
+                println!("   -> {} moved to {}", name, path.display());
+            }
+            // The original still holds private keys, so the backup is private too.
+            let backup = format!("{}.v{}.bak", group_file, from);
+            write_private_file(&backup, content.as_bytes())?;
+            fs::write(&group_file, serde_json::to_string_pretty(&group)?)?;
+            println!(
+                "Migrated {} member key(s). {} now holds public keys only.",
+                keystores.len(),
+                group_file
+            );
+            println!(
+                "The original, private keys included, is in {}; shred it once the keystores unlock.",
+                backup
+            );
+        }
+
+        Commands::ProposalCreate {
+            group_file,
+            keystore_dir,
+            member,
+            recipient,
+            amount,
+            expires_in,
+            replay,
+            store,
+        } => {
+            let group = load_group(&group_file)?;
+            let key = unlock_member(&group, &keystore_dir, &member)?;
+            let vault_address = group.vault_address.clone().ok_or(anyhow!(
+                "'{}' has no vault address; launch first",
+                group_file
+            ))?;
+
+            let mut ledger = load_nonce_ledger(
+                &replay.nonce_ledger,
+                audit_args,
+                group.tee_address.as_deref(),
+            )?;
+            let nonce = ledger.issue(&vault_address);
+            let proposal = TransferProposal::create(
+                &group,
+                &key,
+                &recipient,
+                amount,
+                &replay.chain_id,
+                nonce,
+                unix_now()?.saturating_add(expires_in),
+            )?;
+            let expires_at = proposal.header.expires_at;
+            let event = AuditEvent::ProposalCreated {
+                id: nonce,
+                proposer: proposal.proposer.clone(),
+                vote_digest: hex::encode(proposal.vote_digest()?),
+                recipient: recipient.clone(),
+                amount,
+            };
+            let mut proposals = ProposalStore::load(&store)?;
+            let id = proposals.add(proposal, unix_now()?)?;
+            ledger.save(&replay.nonce_ledger)?;
+            proposals.save(&store)?;
+            audit(audit_args, event)?;
+            println!(
+                "{} proposed {} MIST to {} as proposal #{} (expires {}); stored in {}",
+                member, amount, recipient, id, expires_at, store
+            );
+        }
+
+        Commands::ProposalVote {
+            group_file,
+            keystore_dir,
+            member,
+            id,
+            replay,
+            store,
+        } => {
+            let group = load_group(&group_file)?;
+            let ledger = load_nonce_ledger(
+                &replay.nonce_ledger,
+                audit_args,
+                group.tee_address.as_deref(),
+            )?;
+            let mut proposals = ProposalStore::load(&store)?;
+            let proposal = open_proposal(&mut proposals, &store, &group, id, &replay, &ledger)?;
+            let key = unlock_member(&group, &keystore_dir, &member)?;
+            let address = key.member()?.address;
+
+            let vote_digest = proposal.vote_digest()?;
+            let signature = key.sign_personal_message(&vote_digest)?;
+            let status = proposals.record_vote(&group, id, &address, &signature, unix_now()?)?;
+            proposals.save(&store)?;
+            audit(
+                audit_args,
+                AuditEvent::VoteCast {
+                    id,
+                    voter: address.clone(),
+                    signature,
+                },
+            )?;
+            println!(
+                "{} voted for proposal #{} ({} MIST to {}); {}, now {}",
+                member,
+                id,
+                proposal.amount,
+                proposal.recipient,
+                group.verify_proposal(
+                    &vote_digest,
+                    &proposals.get(id)?.signatures(),
+                    ProposalKind::Transfer,
+                    unix_now()?
+                ),
+                status
+            );
+            if let Some(executable_at) = proposals.get(id)?.executable_at {
+                println!("Executable from {}", executable_at);
+            }
+        }
+
+        Commands::ProposalVeto {
+            group_file,
+            keystore_dir,
+            member,
+            id,
+            replay,
+            store,
+        } => {
+            let group = load_group(&group_file)?;
+            let ledger = load_nonce_ledger(
+                &replay.nonce_ledger,
+                audit_args,
+                group.tee_address.as_deref(),
+            )?;
+            let mut proposals = ProposalStore::load(&store)?;
+            let proposal = open_proposal(&mut proposals, &store, &group, id, &replay, &ledger)?;
+            let key = unlock_member(&group, &keystore_dir, &member)?;
+            let address = key.member()?.address;
+
+            let signature = key.sign_personal_message(&proposal.veto_digest()?)?;
+            let status = proposals.record_veto(&group, id, &address, &signature, unix_now()?)?;
+            proposals.save(&store)?;
+            audit(
+                audit_args,
+                AuditEvent::VetoCast {
+                    id,
+                    guardian: address,
+                    signature,
+                },
+            )?;
+            println!(
+                "{} vetoed proposal #{}; {} of {} veto(es), now {}",
+                member,
+                id,
+                proposals.get(id)?.vetoers(&group)?.len(),
+                group.timelock.veto_threshold,
+                status
+            );
+        }
+
+        Commands::ProposalList {
+            status,
+            nonce_ledger,
+            store,
+        } => {
+            let mut proposals = ProposalStore::load(&store)?;
+            sweep_proposals(
+                &mut proposals,
+                &store,
+                &load_nonce_ledger(&nonce_ledger, audit_args, None)?,
+            )?;
+            let records: Vec<_> = match status {
+                Some(status) => proposals.with_status(status).collect(),
+                None => proposals.proposals.values().collect(),
+            };
+            if records.is_empty() {
+                println!("No proposals.");
+            }
+            for record in records {
+                let p = &record.proposal;
+                println!(
+                    "#{} [{} {}] {} MIST to {} by {} ({} vote(s), expires {})",
+                    p.header.nonce,
+                    u8::from(record.status),
+                    record.status,
+                    p.amount,
+                    p.recipient,
+                    p.proposer,
+                    record.votes.len(),
+                    p.header.expires_at
+                );
+            }
+        }
+
+        Commands::ProposalShow {
+            id,
+            nonce_ledger,
+            store,
+        } => {
+            let mut proposals = ProposalStore::load(&store)?;
+            sweep_proposals(
+                &mut proposals,
+                &store,
+                &load_nonce_ledger(&nonce_ledger, audit_args, None)?,
+            )?;
+            println!("{}", serde_json::to_string_pretty(proposals.get(id)?)?);
+        }
+
+        Commands::ProposalExecute {
+            group_file,
+            keystore_dir,
+            executor,
+            dao_in,
+            tee_in,
+            tee_mpc_key,
+            id,
+            replay,
+            strategy,
+            shards_in,
+            refresh_state,
+            store,
+            spending,
+        } => {
+            let group = load_group(&group_file)?;
+            let mut ledger = load_nonce_ledger(
+                &replay.nonce_ledger,
+                audit_args,
+                group.tee_address.as_deref(),
+            )?;
+            let policy = load_policy(&spending)?;
+            let mut spending_ledger = load_spending_ledger(
+                &spending.spending_ledger,
+                audit_args,
+                group.tee_address.as_deref(),
+            )?;
+            let mut proposals = ProposalStore::load(&store)?;
+            let proposal = open_proposal(&mut proposals, &store, &group, id, &replay, &ledger)?;
+            let executor_key = unlock_member(&group, &keystore_dir, &executor)?;
+            let executor_address = executor_key.member()?.address;
+            let authorization = executor_key.sign_personal_message(&proposal.execute_digest()?)?;
+            drop(executor_key);
+            proposal.verify_execution(&group, &executor_address, &authorization)?;
+
+            // 1. Load s_tee as Scalar
+            let s_tee_bytes = read_secret_hex(&tee_in)?;
+            let s_tee_scalar = bytes_to_scalar(s_tee_bytes.expose_secret())?;
+
+            // 2. Load s_dao as Scalar
+            let s_dao_scalar = load_dao_scalar(&strategy, &dao_in, shards_in, group.threshold)?;
+            let encryption_seed = load_tee_encryption_seed(&tee_mpc_key)?;
+
+            // 3. The shares must control the vault; checked before the costly two-party setup
+            let sender = vault_address(&s_dao_scalar, &s_tee_scalar)?;
+
+            println!(
+                "--- Proposal: Transfer {} MIST to {} ---",
+                proposal.amount, proposal.recipient
+            );
+            println!("Sender (DAO Vault): {}", sender);
+            if let Some(expected) = &group.vault_address {
+                if expected != &sender {
+                    return Err(anyhow!(
+                        "Shares control {}, but the group vault is {}",
+                        sender,
+                        expected
+                    ));
+                }
+            }
+
+            let tx_hash = proposal.tx_digest()?;
+            println!("Transaction Digest: {}", hex::encode(&tx_hash));
+
+            let mut multisig = None;
+            // With NFT sharding, presenting the shards counts as the quorum. Shards
+            // are not tied to members, so there is no weight to check tiers against.
+            let mut approved_weight = group.required_weight();
+            if let Strategy::NftSharding = strategy {
+                if !policy.approval_tiers.is_empty() {
+                    return Err(anyhow!(
+                        "The spending policy has approval tiers, which need counted votes; \
+                         NftSharding approves by presenting shards"
+                    ));
+                }
+            }
+            if let Strategy::Seal = strategy {
+                println!("Collecting Votes for proposal #{} from {}...", id, store);
+                let signatures = proposals.get(id)?.signatures();
+                let vote_digest = proposal.vote_digest()?;
+
+                match group.governance_mode {
+                    GovernanceMode::Seal => {
+                        println!("\n[Seal Smart Contract] Verifying signatures...");
+                        let tally = group.verify_proposal(
+                            &vote_digest,
+                            &signatures,
+                            ProposalKind::Transfer,
+                            unix_now()?,
+                        );
+                        if !tally.passed() {
+                            return Err(anyhow!("Proposal Rejected: {}.", tally));
+                        }
+                        println!("[Seal Smart Contract] Access Granted.");
+                        approved_weight = tally.approved_weight;
+                    }
+                    GovernanceMode::MultiSig => {
+                        println!("\n[Sui MultiSig] Combining signatures...");
+                        let multisig_pk = MultiSigPublicKey::from_group(&group)?;
+                        let combined = MultiSig::combine(&multisig_pk, &vote_digest, &signatures)
+                            .map_err(|e| anyhow!("Proposal Rejected: {}.", e))?;
+                        approved_weight = combined.weight();
+                        let combined = combined.serialize();
+                        let address = verify_personal_message(&vote_digest, &combined)?;
+                        println!("[Sui MultiSig] Approved by {}", address);
+                        println!("MultiSig: {}", combined);
+                        multisig = Some(combined);
+                    }
+                }
+            }
+            policy.check(
+                &spending_ledger,
+                &proposal.recipient,
+                proposal.amount,
+                approved_weight,
+                group.required_weight(),
+                unix_now()?,
+            )?;
+            println!("[Policy] Transfer within the spending policy.");
+            // With NFT sharding, presenting the shards is the approval and starts
+            // the timelock.
+            proposals.mark_passed(&group, id, unix_now()?)?;
+            if let Err(e) = proposals.get(id)?.check_executable(&group, unix_now()?) {
+                proposals.save(&store)?;
+                return Err(e);
+            }
+
+            // Two-party setup: the DAO side and the TEE keep their own shares
+            let mut rng = OsRng;
+            let (mut tee_party, tee_setup) =
+                TeeSigningParty::new(s_tee_scalar, &encryption_seed, &mut rng)?;
+            let (dao_party, key_setup) = DaoSigningParty::new(s_dao_scalar, &tee_setup, &mut rng)?;
+            tee_party.accept_setup(&key_setup, &mut rng)?;
+            if pubkey_to_sui_address(&dao_party.joint_public_key()) != sender {
+                return Err(anyhow!("The two-party key does not match the shares"));
+            }
+
+            // The nonce is spent before signing, so a failed run cannot be retried
+            // with the same votes.
+            ledger.consume(&proposal.header.dao_id, proposal.header.nonce)?;
+            audit(
+                audit_args,
+                AuditEvent::NonceConsumed {
+                    dao_id: proposal.header.dao_id.clone(),
+                    nonce: proposal.header.nonce,
+                },
+            )?;
+            ledger.save(&replay.nonce_ledger)?;
+
+            // The spend counts against the limits before the vault signs and is
+            // rolled back only if signing fails.
+            spending_ledger.record(id, &proposal.recipient, proposal.amount, unix_now()?);
+            spending_ledger.save(&spending.spending_ledger)?;
+
+            println!("\n[TEE] Two-party signing with the DAO share holder...");
+            let mut sign = || {
+                let presign = tee_party.presign(&mut rng)?;
+                let partial = dao_party.sign(&tx_hash, &presign, &mut rng)?;
+                tee_party.finish_signing(&tx_hash, presign, &partial, &mut rng)
+            };
+            let signature = match sign() {
+                Ok(signature) => signature,
+                Err(e) => {
+                    spending_ledger.unrecord(id);
+                    spending_ledger.save(&spending.spending_ledger)?;
+                    return Err(e);
+                }
+            };
+
+            println!("--- Execution Successful ---");
+            println!("Signature: {}", hex::encode(signature.to_bytes()));
+            println!("Status: VALID SIGNATURE FOR SUI NETWORK");
+            proposals.record_execution(
+                id,
+                ExecutionRecord {
+                    executor: executor_address.clone(),
+                    authorization,
+                    executed_at: unix_now()?,
+                    tx_digest: hex::encode(&tx_hash),
+                    signature: hex::encode(signature.to_bytes()),
+                    multisig,
+                },
+            )?;
+            proposals.save(&store)?;
+            audit(
+                audit_args,
+                AuditEvent::ProposalExecuted {
+                    id,
+                    executor: executor_address,
+                    tx_digest: hex::encode(&tx_hash),
+                    signature: hex::encode(signature.to_bytes()),
+                },
+            )?;
+            println!("Proposal #{} recorded as executed in {}", id, store);
+
+            // 4. Proactive refresh, if the schedule says so
+            if fs::metadata(&refresh_state).is_ok() {
+                let mut scheduler = RefreshScheduler::load(&refresh_state)?;
+                scheduler.record_signature();
+                let now = unix_now()?;
+                let digest = group.membership_digest();
+                match strategy {
+                    Strategy::Seal => {
+                        let s_dao_bytes = read_secret_hex(&dao_in)?;
+                        match scheduler.refresh_additive_if_due(
+                            DWalletCurve::Secp256k1 as u32,
+                            s_dao_bytes.expose_secret(),
+                            s_tee_bytes.expose_secret(),
+                            now,
+                            &digest,
+                        )? {
+                            Some((reason, refreshed)) => {
+                                // The TEE share goes last; the epoch only advances once
+                                // both shares are on disk.
+                                let dao_hex = secret_hex(&refreshed.new_dao_share);
+                                let tee_hex = secret_hex(&refreshed.new_tee_share);
+                                replace_files(&[
+                                    (&dao_in, dao_hex.as_bytes()),
+                                    (&tee_in, tee_hex.as_bytes()),
+                                ])?;
+                                scheduler.mark_refreshed(now, &digest);
+                                scheduler.save(&refresh_state)?;
+                                println!(
+                                    "[Scheduler] Shares refreshed ({}). Epoch {}.",
+                                    reason, scheduler.state.epoch
+                                );
+                                audit(
+                                    audit_args,
+                                    AuditEvent::SharesRefreshed {
+                                        epoch: scheduler.state.epoch,
+                                        reason: reason.to_string(),
+                                    },
+                                )?;
+                            }
+                            None => scheduler.save(&refresh_state)?,
+                        }
+                    }
+                    Strategy::NftSharding => {
+                        // Only `threshold` shards are presented here, and a refresh must
+                        // replace all of them, so it stays a manual group-refresh.
+                        if let Some(reason) = scheduler.due(now, &digest) {
+                            println!(
+                                "[Scheduler] Refresh due ({}). Run group-refresh with all shards.",
+                                reason
+                            );
+                        }
+                        scheduler.save(&refresh_state)?;
+                    }
+                }
+            }
+        }
+
+        Commands::GroupRefresh {
+            group_in,
+            dao_in,
+            tee_in,
+            dao_out,
+            tee_out,
+            strategy,
+            shards_in,
+            shards,
+            shard_dir,
+            refresh_state,
+        } => {
+            let group = load_group(&group_in)?;
+            let vault = group
+                .vault_address
+                .clone()
+                .ok_or(anyhow!("'{}' has no vault address; launch first", group_in))?;
+            let s_tee_bytes = read_secret_hex(&tee_in)?;
+
+            println!("--- PSS Refresh Initiated ---");
+            let mut outputs: Vec<(String, Zeroizing<String>)> = Vec::new();
+            let tee_hex;
+            match strategy {
+                Strategy::Seal => {
+                    let s_dao_bytes = read_secret_hex(&dao_in)?;
+                    let old_addr = vault_address(
+                        &bytes_to_scalar(s_dao_bytes.expose_secret())?,
+                        &bytes_to_scalar(s_tee_bytes.expose_secret())?,
+                    )?;
+                    if old_addr != vault {
+                        return Err(anyhow!(
+                            "Shares control {}, but the group vault is {}. Nothing written.",
+                            old_addr,
+                            vault
+                        ));
+                    }
+
+                    let refreshed = perform_pss_refresh(
+                        DWalletCurve::Secp256k1 as u32,
+                        s_dao_bytes.expose_secret(),
+                        s_tee_bytes.expose_secret(),
+                    )?;
+                    let new_addr = vault_address(
+                        &bytes_to_scalar(refreshed.new_dao_share.expose_secret())?,
+                        &bytes_to_scalar(refreshed.new_tee_share.expose_secret())?,
+                    )?;
+                    if new_addr != vault {
+                        return Err(anyhow!(
+                            "Vault address changed during refresh. Nothing written."
+                        ));
+                    }
+                    println!("1. Vault address unchanged: {}", new_addr);
+
+                    let dao_hex = secret_hex(&refreshed.new_dao_share);
+                    outputs.push((dao_out.clone(), dao_hex));
+                    tee_hex = secret_hex(&refreshed.new_tee_share);
+                    println!("2. Re-sealing DAO Share to {}", dao_out);
+                }
+                Strategy::NftSharding => {
+                    let files =
+                        shards_in.ok_or(anyhow!("Strategy NftSharding requires --shards-in"))?;
+                    if files.len() != shards {
+                        return Err(anyhow!(
+                            "Refresh replaces every shard: expected {} files, got {}",
+                            shards,
+                            files.len()
+                        ));
+                    }
+                    let old_shares = load_shards(&files)?;
+                    let s_tee_scalar = bytes_to_scalar(s_tee_bytes.expose_secret())?;
+                    let old_addr = vault_address(
+                        &recover_secret_checked(&old_shares, group.threshold)?,
+                        &s_tee_scalar,
+                    )?;
+                    if old_addr != vault {
+                        return Err(anyhow!(
+                            "Shards control {}, but the group vault is {}. Nothing written.",
+                            old_addr,
+                            vault
+                        ));
+                    }
+
+                    let refreshed = perform_sharded_refresh(
+                        DWalletCurve::Secp256k1 as u32,
+                        s_tee_bytes.expose_secret(),
+                        &old_shares,
+                        group.threshold,
+                        shards,
+                    )?;
+                    let new_addr = vault_address(
+                        &recover_secret_checked(&refreshed.new_dao_shards, group.threshold)?,
+                        &bytes_to_scalar(refreshed.new_tee_share.expose_secret())?,
+                    )?;
+                    if new_addr != vault {
+                        return Err(anyhow!(
+                            "Vault address changed during refresh. Nothing written."
+                        ));
+                    }
+                    println!("1. Vault address unchanged: {}", new_addr);
+
+                    println!("2. Re-minting {} NFT shards...", shards);
+                    fs::create_dir_all(&shard_dir)?;
+                    outputs.extend(shard_files(&shard_dir, &refreshed.new_dao_shards));
+                    tee_hex = secret_hex(&refreshed.new_tee_share);
+                }
+            }
+
+            // Everything is staged first and the TEE share lands last, so a failed
+            // write leaves the old shares (and old shards) usable together. The
+            // epoch advances only after that.
+            let mut files: Vec<(&str, &[u8])> = outputs
+                .iter()
+                .map(|(path, hex)| (path.as_str(), hex.as_bytes()))
+                .collect();
+            files.push((&tee_out, tee_hex.as_bytes()));
+            replace_files(&files)?;
+            let mut epoch = None;
+            if fs::metadata(&refresh_state).is_ok() {
+                let mut scheduler = RefreshScheduler::load(&refresh_state)?;
+                scheduler.mark_refreshed(unix_now()?, &group.membership_digest());
+                scheduler.save(&refresh_state)?;
+                epoch = Some(scheduler.state.epoch);
+            }
+            for (path, _) in &outputs {
+                println!("   -> Wrote {}", path);
+            }
+            println!("3. Stored refreshed TEE Share to {}", tee_out);
+            if let Some(epoch) = epoch {
+                println!("4. Refresh epoch advanced to {}", epoch);
+            }
+            audit(
+                audit_args,
+                AuditEvent::SharesRefreshed {
+                    epoch: epoch.unwrap_or_default(),
+                    reason: "manual".into(),
+                },
+            )?;
+            println!("--- Refresh Complete: old shares are now useless ---");
+        }
+
+        Commands::Audit { command } => {
+            let log = AuditLog::load(&audit_args.audit_log)?;
+            match command {
+                AuditCommands::Verify {
+                    expected_head,
+                    expected_len,
+                    expected_tee,
+                    group_file,
+                } => {
+                    let expected_tee = match expected_tee {
+                        Some(tee) => Some(tee),
+                        None if std::path::Path::new(&group_file).exists() => {
+                            load_group(&group_file)?.tee_address
+                        }
+                        None => None,
+                    };
+                    let report = match expected_head {
+                        Some(hash) => log.verify(
+                            Some(&AuditHead {
+                                len: expected_len.unwrap_or(log.entries.len() as u64),
+                                hash,
+                                tee_signature: None,
+                            }),
+                            expected_tee.as_deref(),
+                        )?,
+                        None => verify_audit_log(&log, audit_args, expected_tee.as_deref())?,
+                    };
+                    if expected_tee.is_none() && report.launched {
+                        println!(
+                            "Note: the TEE identity was taken from the log itself; \
+                             pin it with --expected-tee or the group file's tee_address"
+                        );
+                    }
+                    println!(
+                        "Audit log {} verified: {} entries, {} signed by the TEE ({}), head {}",
+                        audit_args.audit_log,
+                        report.entries,
+                        report.signed,
+                        report.tee_address.as_deref().unwrap_or("not launched"),
+                        log.head().hash
+                    );
+                }
+                AuditCommands::Show => {
+                    if log.entries.is_empty() {
+                        println!("No audit entries.");
+                    }
+                    for entry in &log.entries {
+                        println!(
+                            "#{} at {} [{}{}] {:?}",
+                            entry.seq,
+                            entry.timestamp,
+                            short_hex(&entry.hash),
+                            if entry.tee_signature.is_some() {
+                                ", TEE-signed"
+                            } else {
+                                ""
+                            },
+                            entry.event
+                        );
+                    }
+                }
+            }
+        }
+
+        Commands::PolicyStatus { spending } => {
+            let policy = load_policy(&spending)?;
+            let spending_ledger =
+                load_spending_ledger(&spending.spending_ledger, audit_args, None)?;
+            let now = unix_now()?;
+            let limit =
+                |limit: Option<u64>| limit.map_or("(no limit)".to_string(), |l| l.to_string());
+
+            println!("--- Spending Policy ({}) ---", spending.policy);
+            println!("Per transaction: {}", limit(policy.max_per_transaction));
+            for (name, cap, window) in [
+                ("Daily", policy.daily_limit, DAY_SECS),
+                ("Weekly", policy.weekly_limit, WEEK_SECS),
+            ] {
+                let spent = spending_ledger.spent_since(now.saturating_sub(window));
+                match cap {
+                    Some(cap) => println!(
+                        "{} limit: {} (spent {}, {} left)",
+                        name,
+                        cap,
+                        spent,
+                        cap.saturating_sub(spent)
+                    ),
+                    None => println!("{} limit: (no limit; spent {})", name, spent),
+                }
+            }
+            if !policy.allowlist.is_empty() {
+                println!(
+                    "Allowlist: {}",
+                    policy
+                        .allowlist
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            if !policy.denylist.is_empty() {
+                println!(
+                    "Denylist: {}",
+                    policy
+                        .denylist
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            for tier in &policy.approval_tiers {
+                println!(
+                    "Above {} MIST: quorum + {} approval weight",
+                    tier.above, tier.extra_weight
+                );
+            }
+            println!("Transfers signed: {}", spending_ledger.spends.len());
+        }
+
+        Commands::RefreshStatus {
+            group_file,
+            refresh_state,
+        } => {
+            let group = load_group(&group_file)?;
+            let scheduler = RefreshScheduler::load(&refresh_state)?;
+            let now = unix_now()?;
+
+            println!("--- Refresh Status ---");
+            println!("Epoch: {}", scheduler.state.epoch);
+            println!(
+                "Last refresh: {} ({}s ago)",
+                scheduler.state.last_refresh_at,
+                now.saturating_sub(scheduler.state.last_refresh_at)
+            );
+            println!(
+                "Signatures since refresh: {}",
+                scheduler.state.signatures_since_refresh
+            );
+            match scheduler.signatures_remaining() {
+                Some(left) => println!("Signatures until refresh: {}", left),
+                None => println!("Signatures until refresh: (no limit)"),
+            }
+            match scheduler.next_due_at() {
+                Some(at) => println!(
+                    "Next scheduled refresh: {} (in {}s)",
+                    at,
+                    at.saturating_sub(now)
+                ),
+                None => println!("Next scheduled refresh: (no interval)"),
+            }
+            if scheduler.policy.on_membership_change {
+                let changed = scheduler.state.membership_digest != group.membership_digest();
+                println!("Membership changed since refresh: {}", changed);
+            }
+            match scheduler.due(now, &group.membership_digest()) {
+                Some(reason) => println!("Status: REFRESH DUE ({})", reason),
+                None => println!("Status: OK"),
+            }
+        }
+
+        Commands::BreakGlassRequest {
+            vault_address,
+            recovery_pubkey,
+            reason,
+            valid_for_secs,
+            out,
+        } => {
+            let request = ExportRequest::new(
+                &vault_address,
+                &recovery_pubkey,
+                &reason,
+                unix_now()?,
+                valid_for_secs,
+            )?;
+            fs::write(&out, serde_json::to_string_pretty(&request)?)?;
+            println!("--- Break-Glass Request ---");
+            println!("Vault: {}", request.vault_address);
+            println!("Expires at: {}", request.expires_at);
+            println!("Digest to approve: {}", hex::encode(request.digest()?));
+            println!("Request written to {}", out);
+        }
+
+        Commands::BreakGlassApprove {
+            group_file,
+            request,
+            member,
+            keystore_dir,
+            approvals,
+        } => {
+            let group = load_group(&group_file)?;
+            let request: ExportRequest = serde_json::from_str(&fs::read_to_string(&request)?)?;
+            request.check_window(unix_now()?)?;
+
+            let signer = unlock_member(&group, &keystore_dir, &member)?;
+            group.require_permission(&signer.member()?.address, Permission::Vote)?;
+            let mut signatures: HashMap<String, String> = match fs::read_to_string(&approvals) {
+                Ok(content) => serde_json::from_str(&content)?,
+                Err(_) => HashMap::new(),
+            };
+            signatures.insert(
+                signer.member()?.address,
+                signer.sign_personal_message(&request.digest()?)?,
+            );
+            fs::write(&approvals, serde_json::to_string_pretty(&signatures)?)?;
+            println!(
+                "{} approved the export ({} of {} required approvals collected)",
+                member,
+                signatures.len(),
+                required_approvals(&group)
+            );
+        }
+
+        Commands::BreakGlassExport {
+            group_file,
+            request,
+            approvals,
+            dao_in,
+            tee_in,
+            strategy,
+            shards_in,
+            out,
+            record_out,
+        } => {
+            let group = load_group(&group_file)?;
+            let request: ExportRequest = serde_json::from_str(&fs::read_to_string(&request)?)?;
+            let signatures: HashMap<String, String> =
+                serde_json::from_str(&fs::read_to_string(&approvals)?)?;
+
+            println!("--- Break-Glass Export Initiated ---");
+            println!("Reason: {}", request.reason);
+            let now = unix_now()?;
+            verify_export_approvals(&group, &request, &signatures, now)?;
+
+            let s_tee = bytes_to_scalar(read_secret_hex(&tee_in)?.expose_secret())?;
+            let s_dao = load_dao_scalar(&strategy, &dao_in, shards_in, group.threshold)?;
+            let key = SecretScalar::new(s_dao.expose_secret() + s_tee.expose_secret());
+
+            let (bundle, record) = export_vault_key(&group, &request, &signatures, &key, now)?;
+            drop(key);
+            fs::write(&out, serde_json::to_string_pretty(&bundle)?)?;
+            fs::write(&record_out, serde_json::to_string_pretty(&record)?)?;
+            audit(
+                audit_args,
+                AuditEvent::BreakGlassExported {
+                    vault_address: request.vault_address.clone(),
+                    approvers: record.approvals.iter().map(|a| a.address.clone()).collect(),
+                },
+            )?;
+
+            println!(
+                "1. Approved by {} of {} members (required {}):",
+                record.approvals.len(),
+                record.total_members,
+                record.required_approvals
+            );
+            for approval in &record.approvals {
+                println!("   - {} ({})", approval.member, approval.address);
+            }
+            println!("2. Vault key encrypted to {}", request.recovery_pubkey_hex);
+            println!("3. Bundle written to {}", out);
+            println!("4. Audit record written to {}", record_out);
+            println!("--- Export Complete ---");
+        }
+
+        Commands::BreakGlassOpen {
+            bundle,
+            recovery_key_file,
+            out,
+        } => {
+            let bundle: ExportBundle = serde_json::from_str(&fs::read_to_string(&bundle)?)?;
+            let recovery_key = {
+                let text = Zeroizing::new(
+                    fs::read_to_string(&recovery_key_file)
+                        .map_err(|e| anyhow!("Failed to read '{}': {}", recovery_key_file, e))?,
+                );
+                parse_secp256k1_private_key(&text)?
+            };
+            let key = open_export(&bundle, &recovery_key)?;
+            let raw = SecretBytes::new(key.expose_secret().to_bytes().to_vec());
+            let encoded = Zeroizing::new(encode_sui_private_key(&raw)?);
+            fs::write(&out, encoded.as_bytes())?;
+            println!(
+                "Vault {} key written to {}",
+                bundle.request.vault_address, out
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a group file of any supported schema version and checks every member
+/// address matches its key.
+fn load_group(group_file: &str) -> Result<DaoGroup> {
+    let content = fs::read_to_string(group_file)?;
+    let group =
+        parse_group(&content).map_err(|e| anyhow!("Invalid group file '{}': {}", group_file, e))?;
+    group.validate()?;
+    Ok(group)
+}
+
+fn load_launch_group(group_file: &str) -> Result<DaoGroup> {
+    let group = load_group(group_file)?;
+    if group.members.len() < group.threshold {
+        return Err(anyhow!("Not enough members to launch!"));
+    }
+    group.check_quorum_reachable()?;
+    Ok(group)
+}
+
+fn governance_action(group: &DaoGroup, args: &GovernanceArgs) -> Result<GovernanceAction> {
+    let address_of = |name: &str| -> Result<String> {
+        Ok(group
+            .member_by_name(name)
+            .ok_or(anyhow!("Member '{}' not found", name))?
+            .address
+            .clone())
+    };
+
+    let mut actions = Vec::new();
+    if let (Some(name), Some(weight)) = (&args.set_weight, args.weight) {
+        actions.push(GovernanceAction::SetWeight {
+            address: address_of(name)?,
+            weight,
+        });
+    }
+    if let Some(weight) = args.set_quorum_weight {
+        actions.push(GovernanceAction::SetQuorumWeight { weight });
+    }
+    if let (Some(name), Some(roles)) = (&args.set_roles, &args.roles) {
+        actions.push(GovernanceAction::SetRoles {
+            address: address_of(name)?,
+            roles: roles.iter().copied().collect(),
+        });
+    }
+    if let Some(spec) = &args.set_role_threshold {
+        let (role, count) = parse_role_threshold(spec)?;
+        actions.push(GovernanceAction::SetRoleThreshold { role, count });
+    }
+    if let Some(name) = &args.remove_member {
+        actions.push(GovernanceAction::RemoveMember {
+            address: address_of(name)?,
+        });
+    }
+    if let (Some(name), Some(new_pubkey_hex)) = (&args.rotate_key, &args.new_pubkey) {
+        let member = group
+            .member_by_name(name)
+            .ok_or(anyhow!("Member '{}' not found", name))?;
+        actions.push(GovernanceAction::RotateKey {
+            address: member.address.clone(),
+            new_pubkey_hex: new_pubkey_hex.clone(),
+            new_scheme: args.new_scheme.unwrap_or(member.scheme),
+        });
+    }
+    if let Some(threshold) = args.set_threshold {
+        actions.push(GovernanceAction::SetThreshold { threshold });
+    }
+    if let (Some(name), Some(pubkey_hex)) = (&args.add_member, &args.new_pubkey) {
+        let member = Member::from_pubkey(name, args.new_scheme.unwrap_or_default(), pubkey_hex)?;
+        actions.push(GovernanceAction::AddMember {
+            member: Member {
+                weight: args.weight.unwrap_or(member.weight),
+                roles: match &args.roles {
+                    Some(roles) => roles.iter().copied().collect(),
+                    None => member.roles,
+                },
+                ..member
+            },
+        });
+    }
+    if let Some(delay_secs) = args.set_timelock {
+        actions.push(GovernanceAction::SetTimelock {
+            timelock: Timelock {
+                delay_secs,
+                veto_threshold: args.veto_threshold.unwrap_or(group.timelock.veto_threshold),
+            },
+        });
+    }
+
+    match <[GovernanceAction; 1]>::try_from(actions) {
+        Ok([action]) => Ok(action),
+        Err(_) => Err(anyhow!(
+            "Specify exactly one change: --set-weight, --set-quorum-weight, --set-roles, \
+             --set-role-threshold, --remove-member, --rotate-key, --set-threshold, --set-timelock \
+             or --add-member"
+        )),
+    }
+}
+
+/// `<dir>/<name>.pending.json`: a rotated key awaiting governance approval.
+fn pending_keystore_path(keystore_dir: &str, name: &str) -> Result<std::path::PathBuf> {
+    let path = keystore_path(keystore_dir, name)?;
+    Ok(path.with_extension("pending.json"))
+}
+
+/// After an applied rotation or addition, replaces the member's keystore with
+/// the pending one and shreds any retired key.
+fn activate_rotated_keystore(
+    group: &DaoGroup,
+    keystore_dir: &str,
+    new_pubkey_hex: &str,
+) -> Result<()> {
+    let member = group
+        .members
+        .iter()
+        .find(|m| m.pubkey_hex == new_pubkey_hex)
+        .ok_or(anyhow!(
+            "Rotated key {} is not in the group",
+            new_pubkey_hex
+        ))?;
+    let pending = pending_keystore_path(keystore_dir, &member.name)?;
+    if !pending.exists() || MemberKeystore::load(&pending)?.member()?.address != member.address {
+        println!(
+            "No pending keystore for {} in {}; install the new key's keystore as {}.",
+            member.name,
+            keystore_dir,
+            keystore_path(keystore_dir, &member.name)?.display()
+        );
+        return Ok(());
+    }
+    let active = keystore_path(keystore_dir, &member.name)?;
+    if active.exists() {
+        shred_file(&active.to_string_lossy())?;
+    }
+    fs::rename(&pending, &active)?;
+    println!(
+        "{} now signs as {} ({}).",
+        member.name,
+        member.address,
+        active.display()
+    );
+    Ok(())
+}
+
+/// Parses `ROLE=N`, e.g. `guardian=1`.
+fn parse_role_threshold(spec: &str) -> Result<(Role, usize)> {
+    let (role, count) = spec
+        .split_once('=')
+        .ok_or(anyhow!("Expected ROLE=N, got '{}'", spec))?;
+    Ok((role.parse()?, count.trim().parse()?))
+}
+
+fn load_policy(args: &PolicyArgs) -> Result<SpendingPolicy> {
+    if !args.no_policy {
+        return SpendingPolicy::load(&args.policy);
+    }
+    if fs::metadata(&args.policy).is_ok() {
+        return Err(anyhow!(
+            "--no-policy given, but '{}' exists; remove one or the other",
+            args.policy
+        ));
+    }
+    Ok(SpendingPolicy::default())
+}
+
+/// First 16 characters of a digest for display; shorter strings are shown whole.
+fn short_hex(hex: &str) -> &str {
+    hex.get(..16).unwrap_or(hex)
+}
+
+fn format_roles(member: &Member) -> String {
+    member
+        .roles
+        .iter()
+        .map(|r| r.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Fails stale proposals in the store, saving it if anything changed.
+fn sweep_proposals(proposals: &mut ProposalStore, store: &str, ledger: &NonceLedger) -> Result<()> {
+    if !proposals.sweep(unix_now()?, ledger).is_empty() {
+        proposals.save(store)?;
+    }
+    Ok(())
+}
+
+/// Looks up a stored proposal and checks it is live for this group's vault and
+/// chain and was signed by a member with the proposer role.
+fn open_proposal(
+    proposals: &mut ProposalStore,
+    store: &str,
+    group: &DaoGroup,
+    id: u64,
+    replay: &ReplayArgs,
+    ledger: &NonceLedger,
+) -> Result<TransferProposal> {
+    sweep_proposals(proposals, store, ledger)?;
+    let record = proposals.get(id)?;
+    if matches!(
+        record.status,
+        ProposalStatus::Failed | ProposalStatus::Executed
+    ) {
+        return Err(anyhow!(
+            "Proposal #{} is {}{}",
+            id,
+            record.status,
+            record
+                .failure
+                .as_ref()
+                .map(|reason| format!(": {}", reason))
+                .unwrap_or_default()
+        ));
+    }
+    record
+        .proposal
+        .verify(group, &replay.chain_id, unix_now()?, ledger)?;
+    Ok(record.proposal.clone())
+}
+
+/// Generates a member key, stores it encrypted in `<keystore_dir>/<name>.json`
+/// and returns the public entry for the group file.
+fn create_member_keystore(
+    group: &DaoGroup,
+    name: &str,
+    weight: u64,
+    roles: &[Role],
+    scheme: SignatureScheme,
+    keystore_dir: &str,
+) -> Result<Member> {
+    if group.vault_address.is_some() {
+        return Err(anyhow!(
+            "The group is launched; stage a key with member-create-key and add it with \
+             governance-approve --add-member"
+        ));
+    }
+    if group.member_by_name(name).is_some() {
+        return Err(anyhow!("Member '{}' already exists", name));
+    }
+    if weight == 0 {
+        return Err(anyhow!("Voting weight must be positive"));
+    }
+    if roles.is_empty() {
+        return Err(anyhow!("A member needs at least one role"));
+    }
+    let passphrase = read_passphrase(&format!("New passphrase for {}", name))?;
+    let key = MemberKey::generate_scheme(name, scheme);
+    let member = Member {
+        weight,
+        roles: roles.iter().copied().collect(),
+        ..key.member()?
+    };
+    if group.member(&member.address).is_some() {
+        return Err(anyhow!("Address {} is already a member", member.address));
+    }
+    let path = keystore_path(keystore_dir, name)?;
+    MemberKeystore::encrypt(&key, passphrase.as_bytes())?.save(&path)?;
+    println!("Keystore written to {}", path.display());
+    Ok(member)
+}
+
+/// Unlocks a single member's keystore, checking it belongs to that group member.
+fn unlock_member(group: &DaoGroup, keystore_dir: &str, name: &str) -> Result<MemberKey> {
+    let member = group
+        .member_by_name(name)
+        .ok_or(anyhow!("Member '{}' not found", name))?;
+    let keystore = MemberKeystore::load(&keystore_path(keystore_dir, name)?)?;
+    if keystore.member()?.address != member.address {
+        return Err(anyhow!(
+            "Keystore for '{}' does not match the group file",
+            name
+        ));
+    }
+    let passphrase = read_passphrase(&format!("Passphrase for {}", name))?;
+    keystore.unlock(passphrase.as_bytes())
+}
+
+fn read_passphrase(prompt: &str) -> Result<Zeroizing<String>> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    eprint!("{}: ", prompt);
+    std::io::stderr().flush()?;
+    let mut line = Zeroizing::new(String::new());
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(Zeroizing::new(
+        line.trim_end_matches(['\r', '\n']).to_string(),
+    ))
+}
+
+/// Stores the genesis shares, the refresh schedule and the vault address in the
+/// group file. With `expected_address`, nothing is written unless the shares (and
+/// shards) control that address.
+fn launch_vault(
+    launch: &LaunchArgs,
+    audit_args: &AuditArgs,
+    group: &mut DaoGroup,
+    s_dao: &SecretBytes,
+    s_tee: &SecretBytes,
+    expected_address: Option<&str>,
+) -> Result<()> {
+    if let (Some(previous), false) = (&group.vault_address, launch.force) {
+        return Err(anyhow!(
+            "'{}' is already launched as {}; pass --force to replace the vault",
+            launch.group_file,
+            previous
+        ));
+    }
+    let (_, pubkey_hex) =
+        mock_sign_and_verify(s_dao.expose_secret(), s_tee.expose_secret(), b"init")?;
+    let pubkey_bytes = hex::decode(&pubkey_hex)?;
+    let verifying_key = VerifyingKey::from_sec1_bytes(&pubkey_bytes)?;
+    let sui_addr = pubkey_to_sui_address(&verifying_key);
+    if let Some(expected) = expected_address {
+        if sui_addr != expected {
+            return Err(anyhow!(
+                "Shares control {}, expected {}. Nothing written.",
+                sui_addr,
+                expected
+            ));
+        }
+    }
+
+    println!("3. DAO Sui Address Generated: {}", sui_addr);
+    if let Some(previous) = &group.vault_address {
+        println!(
+            "WARN: Replacing vault {}; anything left there needs its old shares.",
+            previous
+        );
+        audit(
+            audit_args,
+            AuditEvent::Relaunched {
+                previous_vault: previous.clone(),
+            },
+        )?;
+    }
+
+    match launch.strategy {
+        Strategy::Seal => {
+            println!("4. Sealing DAO Share to Walrus...");
+            write_secret_hex(&launch.dao_out, s_dao)?;
+        }
+        Strategy::NftSharding => {
+            println!(
+                "4. Sharding DAO Share into {} NFT Blobs (Threshold: {})...",
+                launch.shards, group.threshold
+            );
+            let s_dao_scalar = bytes_to_scalar(s_dao.expose_secret())?;
+            let shares = split_secret(&s_dao_scalar, group.threshold, launch.shards);
+            let s_tee_scalar = bytes_to_scalar(s_tee.expose_secret())?;
+            let recovered = recover_secret_checked(&shares, group.threshold)?;
+            if vault_address(&recovered, &s_tee_scalar)? != sui_addr {
+                return Err(anyhow!(
+                    "Shards do not recover the vault key. Nothing written."
+                ));
+            }
+            fs::create_dir_all(&launch.shard_dir)?;
+            for (path, hex) in shard_files(&launch.shard_dir, &shares) {
+                write_private_file(&path, hex.as_bytes())?;
+                println!("   -> Minted NFT linked to {}", path);
+            }
+        }
+    }
+
+    println!("5. Storing TEE Share...");
+    write_secret_hex(&launch.tee_out, s_tee)?;
+    if fs::metadata(&launch.tee_mpc_key).is_err() {
+        let mut seed = vec![0u8; ENCRYPTION_SEED_LEN];
+        OsRng.fill_bytes(&mut seed);
+        write_secret_hex(&launch.tee_mpc_key, &SecretBytes::new(seed))?;
+        println!("   -> TEE MPC key written to {}", launch.tee_mpc_key);
+    }
+
+    let policy = RefreshPolicy {
+        max_signatures: launch.refresh_after_signatures,
+        max_interval_secs: launch.refresh_interval_secs,
+        on_membership_change: launch.refresh_on_membership_change,
+    };
+    RefreshScheduler::new(policy, unix_now()?, &group.membership_digest())
+        .save(&launch.refresh_state)?;
+    println!("6. Refresh schedule written to {}", launch.refresh_state);
+
+    let tee = match load_tee_identity(&audit_args.tee_identity)? {
+        Some(tee) => tee,
+        None => {
+            let tee = MemberKey::generate("TEE");
+            let encoded = Zeroizing::new(encode_sui_private_key(tee.secret())?);
+            write_private_file(&audit_args.tee_identity, encoded.as_bytes())?;
+            tee
+        }
+    };
+    let tee_address = tee.member()?.address;
+    println!(
+        "7. TEE identity {} (key in {})",
+        tee_address, audit_args.tee_identity
+    );
+
+    group.vault_address = Some(sui_addr.clone());
+    group.tee_address = Some(tee_address.clone());
+    fs::write(&launch.group_file, serde_json::to_string_pretty(&*group)?)?;
+    audit(
+        audit_args,
+        AuditEvent::Launched {
+            vault_address: sui_addr,
+            strategy: format!("{:?}", launch.strategy),
+            membership_digest: group.membership_digest(),
+            tee_address,
+        },
+    )
+}
+
+/// The TEE identity key, if this machine holds it.
+fn load_tee_identity(path: &str) -> Result<Option<MemberKey>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => Zeroizing::new(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(anyhow!("Failed to read TEE identity '{}': {}", path, e)),
+    };
+    let raw = decode_sui_private_key(text.trim())?;
+    Ok(Some(MemberKey::from_bytes(
+        "TEE",
+        SignatureScheme::Secp256k1,
+        raw.expose_secret(),
+    )?))
+}
+
+/// Verifies `log` against its head file, which must be TEE-signed once the
+/// vault is launched.
+fn verify_audit_log(
+    log: &AuditLog,
+    audit_args: &AuditArgs,
+    expected_tee: Option<&str>,
+) -> Result<AuditReport> {
+    let path = &audit_args.audit_log;
+    let head = AuditLog::load_head(path)?;
+    if head.is_none() && !log.entries.is_empty() {
+        return Err(anyhow!(
+            "No head for {}; pass --expected-head to check for truncation",
+            path
+        ));
+    }
+    let report = log
+        .verify(head.as_ref(), expected_tee)
+        .map_err(|e| anyhow!("Audit log '{}': {}", path, e))?;
+    if report.launched && head.is_some_and(|head| head.tee_signature.is_none()) {
+        return Err(anyhow!(
+            "Audit log '{}': the head is not signed by the TEE",
+            path
+        ));
+    }
+    Ok(report)
+}
+
+/// The nonce ledger, plus every nonce the (verified) audit log records as
+/// consumed. `expected_tee` is the group's pinned TEE identity, when known.
+fn load_nonce_ledger(
+    path: &str,
+    audit_args: &AuditArgs,
+    expected_tee: Option<&str>,
+) -> Result<NonceLedger> {
+    let mut ledger = NonceLedger::load(path)?;
+    let log = AuditLog::load(&audit_args.audit_log)?;
+    verify_audit_log(&log, audit_args, expected_tee)?;
+    ledger.absorb_audit_log(&log);
+    Ok(ledger)
+}
+
+/// The spending ledger, plus every transfer the (verified) audit log records
+/// as executed.
+fn load_spending_ledger(
+    path: &str,
+    audit_args: &AuditArgs,
+    expected_tee: Option<&str>,
+) -> Result<SpendingLedger> {
+    let mut ledger = SpendingLedger::load(path)?;
+    let log = AuditLog::load(&audit_args.audit_log)?;
+    verify_audit_log(&log, audit_args, expected_tee)?;
+    ledger.absorb_audit_log(&log);
+    Ok(ledger)
+}
+
+/// Appends `event` to the audit log, signed by the TEE identity when present.
+fn audit(args: &AuditArgs, event: AuditEvent) -> Result<()> {
+    let tee = load_tee_identity(&args.tee_identity)?;
+    AuditLog::append_to_file(&args.audit_log, event, unix_now()?, tee.as_ref())?;
+    Ok(())
+}
+
+fn member_joined(member: &Member) -> AuditEvent {
+    AuditEvent::MemberJoined {
+        name: member.name.clone(),
+        address: member.address.clone(),
+        scheme: member.scheme,
+        weight: member.weight,
+    }
+}
+
+/// Overwrites a key file with zeros before removing it.
+fn shred_file(path: &str) -> Result<()> {
+    let len = fs::metadata(path)?.len() as usize;
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    file.write_all(&vec![0u8; len])?;
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+/// The DAO share, unsealed (Seal) or interpolated from NFT shards (NftSharding).
+fn load_dao_scalar(
+    strategy: &Strategy,
+    dao_in: &str,
+    shards_in: Option<Vec<String>>,
+    threshold: usize,
+) -> Result<SecretScalar> {
+    match strategy {
+        Strategy::Seal => bytes_to_scalar(read_secret_hex(dao_in)?.expose_secret()),
+        Strategy::NftSharding => {
+            let files = shards_in.ok_or(anyhow!("Strategy NftSharding requires --shards-in"))?;
+            if files.len() < threshold {
+                return Err(anyhow!(
+                    "Not enough shards! Need {}, got {}",
+                    threshold,
+                    files.len()
+                ));
+            }
+            println!("[TEE] Collecting shards from NFT holders...");
+            let shares = load_shards(&files)?;
+            println!("[TEE] Interpolating Secret from {} shards...", shares.len());
+            recover_secret_checked(&shares, threshold)
+        }
+    }
+}
+
+/// Shard files are named `shard_<index>.hex`; the index is the Shamir x-coordinate.
+fn load_shards(files: &[String]) -> Result<Vec<Shard>> {
+    let mut shares = Vec::new();
+    for file in files {
+        let bytes = read_secret_hex(file)?;
+        let idx: usize = std::path::Path::new(file)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("shard_")?.strip_suffix(".hex"))
+            .and_then(|idx| idx.parse().ok())
+            .ok_or(anyhow!(
+                "Shard file {} is not named shard_<index>.hex",
+                file
+            ))?;
+
+        if idx == 0 {
+            return Err(anyhow!("Shard {} has index 0; shards start at 1", file));
+        }
+        if shares.iter().any(|(seen, _)| *seen == idx) {
+            return Err(anyhow!(
+                "Shard {} repeats index {}; each shard must be distinct",
+                file,
+                idx
+            ));
+        }
+
+        let scalar = scalar_from_be_bytes(bytes.expose_secret())
+            .map_err(|e| anyhow!("Invalid scalar in shard {}: {}", file, e))?;
+        shares.push((idx, SecretScalar::new(scalar)));
+        println!("   -> Loaded shard from {}", file);
+    }
+    Ok(shares)
+}
+
+/// `(path, hex contents)` of every shard file under `dir`.
+fn shard_files(dir: &str, shares: &[Shard]) -> Vec<(String, Zeroizing<String>)> {
+    shares
+        .iter()
+        .map(|(idx, share)| {
+            let path = std::path::Path::new(dir).join(format!("shard_{}.hex", idx));
+            let bytes = SecretBytes::new(share.expose_secret().to_bytes().to_vec());
+            (path.to_string_lossy().into_owned(), secret_hex(&bytes))
+        })
+        .collect()
+}
+
+fn vault_address(s_dao: &SecretScalar, s_tee: &SecretScalar) -> Result<String> {
+    key_address(&SecretScalar::new(
+        s_dao.expose_secret() + s_tee.expose_secret(),
+    ))
+}
+
+fn key_address(key: &SecretScalar) -> Result<String> {
+    let signing_key = SigningKey::from_bytes(&key.expose_secret().to_bytes())?;
+    Ok(pubkey_to_sui_address(&VerifyingKey::from(&signing_key)))
+}
+
+/// Reads a hex-encoded secret file; the hex text is wiped after decoding.
+fn read_secret_hex(path: &str) -> Result<SecretBytes> {
+    let text = Zeroizing::new(
+        fs::read_to_string(path).map_err(|e| anyhow!("Failed to read '{}': {}", path, e))?,
+    );
+    Ok(SecretBytes::new(hex::decode(text.trim())?))
+}
+
+/// The seed of the TEE's class-groups key, written at launch.
+fn load_tee_encryption_seed(path: &str) -> Result<SecretBytes> {
+    if fs::metadata(path).is_err() {
+        return Err(anyhow!(
+            "TEE MPC key '{}' not found; it is created at launch",
+            path
+        ));
+    }
+    read_secret_hex(path)
+}
+
+fn write_secret_hex(path: &str, secret: &SecretBytes) -> Result<()> {
+    write_private_file(path, secret_hex(secret).as_bytes())
+}
+
+fn secret_hex(secret: &SecretBytes) -> Zeroizing<String> {
+    Zeroizing::new(hex::encode(secret.expose_secret()))
+}
+
+/// Writes and syncs a file only the owner can read.
+fn write_private_file(path: &str, content: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| anyhow!("Failed to write '{}': {}", path, e))?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Replaces a set of files that must change together, such as a share pair.
+/// Every new file is written as `<path>.new` first; then each target is moved
+/// to `<path>.old` and its replacement renamed into place, in order. The `.old`
+/// copies are shredded only after the last rename, so put the file that commits
+/// the change (the TEE share) last: until it lands, the old set is intact.
+fn replace_files(files: &[(&str, &[u8])]) -> Result<()> {
+    for (path, _) in files {
+        let old = format!("{}.old", path);
+        if fs::metadata(&old).is_ok() {
+            return Err(anyhow!(
+                "{} is left from an interrupted update; restore or remove it first",
+                old
+            ));
+        }
+    }
+    for (path, content) in files {
+        write_private_file(&format!("{}.new", path), content)?;
+    }
+    for (path, _) in files {
+        if fs::metadata(path).is_ok() {
+            fs::rename(path, format!("{}.old", path))?;
+        }
+        fs::rename(format!("{}.new", path), path)?;
+    }
+    for (path, _) in files {
+        let old = format!("{}.old", path);
+        if fs::metadata(&old).is_ok() {
+            shred_file(&old)?;
+        }
+    }
     Ok(())
 }
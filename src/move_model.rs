@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::proposal_store::ProposalStatus;
+
+// Reference model of `move/sovereign_dao/sources/dao.move`.
+//
+// Each entry function checks the same conditions in the same order and aborts
+// with the same code, so the TEE can predict `seal_approve` before dry-running
+// it, and a trace of `Call`s can be replayed against both implementations.
+// Shared objects live in `SovereignDao`; object ids are allocated sequentially
+// from 1 instead of being derived from the transaction digest. Passing an id
+// that does not exist fails with `ObjectNotFound`: on-chain such a transaction
+// is rejected before the module runs, so that code has no Move counterpart.
+
+pub type Address = String;
+pub type ObjectId = u64;
+
+/// `action_type` values understood by `execute_proposal`.
+pub const ACTION_NONE: u8 = 0;
+pub const ACTION_ADD_TEE: u8 = 1;
+pub const ACTION_REMOVE_TEE: u8 = 2;
+
+/// The module's abort codes.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveAbort {
+    #[error("ENotMember (0)")]
+    NotMember = 0,
+    #[error("EProposalNotPassed (1)")]
+    ProposalNotPassed = 1,
+    #[error("EInvalidProposalId (2)")]
+    InvalidProposalId = 2,
+    #[error("ENotTrustedTEE (3)")]
+    NotTrustedTee = 3,
+    #[error("EAlreadyVoted (4)")]
+    AlreadyVoted = 4,
+    #[error("EProposalExecuted (5)")]
+    ProposalExecuted = 5,
+    #[error("EInvalidEncryptionId (6)")]
+    InvalidEncryptionId = 6,
+    /// Not a module abort; kept clear of the module's codes.
+    #[error("object not found (100)")]
+    ObjectNotFound = 100,
+}
+
+impl MoveAbort {
+    pub fn code(self) -> u64 {
+        self as u64
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Dao {
+    pub id: ObjectId,
+    pub name: String,
+    /// `VecSet<address>`: insertion order, no duplicates.
+    pub members: Vec<Address>,
+    pub threshold: u64,
+    pub trusted_tees: Vec<Address>,
+    pub encryption_id: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Proposal {
+    pub id: ObjectId,
+    pub dao_id: ObjectId,
+    pub proposer: Address,
+    pub title: String,
+    pub description: String,
+    pub votes: Vec<Address>,
+    pub status: ProposalStatus,
+    pub action_type: u8,
+    pub action_target: Address,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    ProposalCreated {
+        proposal_id: ObjectId,
+        proposer: Address,
+        action_type: u8,
+        action_target: Address,
+    },
+    Voted {
+        proposal_id: ObjectId,
+        voter: Address,
+    },
+    ProposalExecuted {
+        proposal_id: ObjectId,
+        executor: Address,
+    },
+}
+
+/// One entry-function call, as recorded in a differential trace.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Call {
+    CreateDao {
+        name: String,
+        members: Vec<Address>,
+        threshold: u64,
+        encryption_id: Vec<u8>,
+    },
+    CreateProposal {
+        dao: ObjectId,
+        title: String,
+        description: String,
+        action_type: u8,
+        action_target: Address,
+    },
+    Vote {
+        dao: ObjectId,
+        proposal: ObjectId,
+    },
+    ExecuteProposal {
+        dao: ObjectId,
+        proposal: ObjectId,
+    },
+    SealApprove {
+        id: Vec<u8>,
+        dao: ObjectId,
+        proposal: ObjectId,
+    },
+}
+
+/// The shared objects and emitted events of one simulated chain.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SovereignDao {
+    pub daos: BTreeMap<ObjectId, Dao>,
+    pub proposals: BTreeMap<ObjectId, Proposal>,
+    pub events: Vec<Event>,
+    next_id: ObjectId,
+}
+
+impl SovereignDao {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn new_id(&mut self) -> ObjectId {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub fn dao(&self, id: ObjectId) -> Option<&Dao> {
+        self.daos.get(&id)
+    }
+
+    pub fn proposal(&self, id: ObjectId) -> Option<&Proposal> {
+        self.proposals.get(&id)
+    }
+
+    pub fn create_dao(
+        &mut self,
+        name: &str,
+        members: &[Address],
+        threshold: u64,
+        encryption_id: &[u8],
+    ) -> ObjectId {
+        let mut members_set: Vec<Address> = Vec::new();
+        for member in members {
+            if !members_set.contains(member) {
+                members_set.push(member.clone());
+            }
+        }
+        let id = self.new_id();
+        self.daos.insert(
+            id,
+            Dao {
+                id,
+                name: name.to_string(),
+                members: members_set,
+                threshold,
+                trusted_tees: Vec::new(),
+                encryption_id: encryption_id.to_vec(),
+            },
+        );
+        id
+    }
+
+    pub fn create_proposal(
+        &mut self,
+        sender: &str,
+        dao: ObjectId,
+        title: &str,
+        description: &str,
+        action_type: u8,
+        action_target: &str,
+    ) -> Result<ObjectId, MoveAbort> {
+        let dao = self.daos.get(&dao).ok_or(MoveAbort::ObjectNotFound)?;
+        if !is_member(dao, sender) {
+            return Err(MoveAbort::NotMember);
+        }
+
+        let dao_id = dao.id;
+        let id = self.new_id();
+        self.proposals.insert(
+            id,
+            Proposal {
+                id,
+                dao_id,
+                proposer: sender.to_string(),
+                title: title.to_string(),
+                description: description.to_string(),
+                votes: Vec::new(),
+                status: ProposalStatus::Active,
+                action_type,
+                action_target: action_target.to_string(),
+            },
+        );
+        self.events.push(Event::ProposalCreated {
+            proposal_id: id,
+            proposer: sender.to_string(),
+            action_type,
+            action_target: action_target.to_string(),
+        });
+        Ok(id)
+    }
+
+    pub fn vote(
+        &mut self,
+        sender: &str,
+        dao: ObjectId,
+        proposal: ObjectId,
+    ) -> Result<(), MoveAbort> {
+        let dao = self.daos.get(&dao).ok_or(MoveAbort::ObjectNotFound)?;
+        let proposal = self
+            .proposals
+            .get_mut(&proposal)
+            .ok_or(MoveAbort::ObjectNotFound)?;
+        if !is_member(dao, sender) {
+            return Err(MoveAbort::NotMember);
+        }
+        if dao.id != proposal.dao_id {
+            return Err(MoveAbort::InvalidProposalId);
+        }
+        if proposal.votes.iter().any(|v| v == sender) {
+            return Err(MoveAbort::AlreadyVoted);
+        }
+        // Only vote on Active
+        if proposal.status != ProposalStatus::Active {
+            return Err(MoveAbort::ProposalExecuted);
+        }
+
+        proposal.votes.push(sender.to_string());
+        self.events.push(Event::Voted {
+            proposal_id: proposal.id,
+            voter: sender.to_string(),
+        });
+        if proposal.votes.len() as u64 >= dao.threshold {
+            proposal.status = ProposalStatus::Passed;
+        }
+        Ok(())
+    }
+
+    /// Any sender may execute a passed proposal, as on-chain.
+    pub fn execute_proposal(
+        &mut self,
+        sender: &str,
+        dao: ObjectId,
+        proposal: ObjectId,
+    ) -> Result<(), MoveAbort> {
+        let dao = self.daos.get_mut(&dao).ok_or(MoveAbort::ObjectNotFound)?;
+        let proposal = self
+            .proposals
+            .get_mut(&proposal)
+            .ok_or(MoveAbort::ObjectNotFound)?;
+        if dao.id != proposal.dao_id {
+            return Err(MoveAbort::InvalidProposalId);
+        }
+        if proposal.status != ProposalStatus::Passed {
+            return Err(MoveAbort::ProposalNotPassed);
+        }
+
+        let target = &proposal.action_target;
+        match proposal.action_type {
+            ACTION_ADD_TEE if !dao.trusted_tees.contains(target) => {
+                dao.trusted_tees.push(target.clone())
+            }
+            ACTION_REMOVE_TEE => dao.trusted_tees.retain(|t| t != target),
+            _ => {}
+        }
+
+        proposal.status = ProposalStatus::Executed;
+        self.events.push(Event::ProposalExecuted {
+            proposal_id: proposal.id,
+            executor: sender.to_string(),
+        });
+        Ok(())
+    }
+
+    /// What the Seal key server dry-runs before releasing the DAO share's key.
+    pub fn seal_approve(
+        &self,
+        sender: &str,
+        id: &[u8],
+        dao: ObjectId,
+        proposal: ObjectId,
+    ) -> Result<(), MoveAbort> {
+        let dao = self.daos.get(&dao).ok_or(MoveAbort::ObjectNotFound)?;
+        let proposal = self
+            .proposals
+            .get(&proposal)
+            .ok_or(MoveAbort::ObjectNotFound)?;
+        if id != dao.encryption_id.as_slice() {
+            return Err(MoveAbort::InvalidEncryptionId);
+        }
+        if dao.id != proposal.dao_id {
+            return Err(MoveAbort::InvalidProposalId);
+        }
+        if !matches!(
+            proposal.status,
+            ProposalStatus::Passed | ProposalStatus::Executed
+        ) {
+            return Err(MoveAbort::ProposalNotPassed);
+        }
+        if !is_trusted_tee(dao, sender) {
+            return Err(MoveAbort::NotTrustedTee);
+        }
+        Ok(())
+    }
+
+    /// Runs one traced call. Returns the id of the object it created, if any.
+    pub fn apply(&mut self, sender: &str, call: &Call) -> Result<Option<ObjectId>, MoveAbort> {
+        match call {
+            Call::CreateDao {
+                name,
+                members,
+                threshold,
+                encryption_id,
+            } => Ok(Some(self.create_dao(
+                name,
+                members,
+                *threshold,
+                encryption_id,
+            ))),
+            Call::CreateProposal {
+                dao,
+                title,
+                description,
+                action_type,
+                action_target,
+            } => self
+                .create_proposal(
+                    sender,
+                    *dao,
+                    title,
+                    description,
+                    *action_type,
+                    action_target,
+                )
+                .map(Some),
+            Call::Vote { dao, proposal } => self.vote(sender, *dao, *proposal).map(|_| None),
+            Call::ExecuteProposal { dao, proposal } => {
+                self.execute_proposal(sender, *dao, *proposal).map(|_| None)
+            }
+            Call::SealApprove { id, dao, proposal } => {
+                self.seal_approve(sender, id, *dao, *proposal).map(|_| None)
+            }
+        }
+    }
+
+    /// Replays `(sender, call)` pairs; a call that aborts leaves the state as it was.
+    pub fn run(&mut self, trace: &[(Address, Call)]) -> Vec<Result<Option<ObjectId>, MoveAbort>> {
+        trace
+            .iter()
+            .map(|(sender, call)| self.apply(sender, call))
+            .collect()
+    }
+}
+
+// Getters, as exposed by the module.
+
+pub fn encryption_id(dao: &Dao) -> &[u8] {
+    &dao.encryption_id
+}
+
+pub fn proposal_status(proposal: &Proposal) -> u8 {
+    proposal.status.into()
+}
+
+pub fn is_trusted_tee(dao: &Dao, addr: &str) -> bool {
+    dao.trusted_tees.iter().any(|t| t == addr)
+}
+
+pub fn is_member(dao: &Dao, addr: &str) -> bool {
+    dao.members.iter().any(|m| m == addr)
+}
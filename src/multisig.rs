@@ -0,0 +1,304 @@
+use anyhow::{Result, anyhow};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use std::collections::HashMap;
+
+use crate::dao::{DaoGroup, Permission};
+use crate::sui_utils::{
+    MULTISIG_FLAG, SignatureScheme, public_key_to_sui_address, serialize_signature,
+    verify_personal_message,
+};
+
+// Sui's native k-of-n account: the voting members' keys and weights with the
+// quorum as threshold. Approvals combine into one `MultiSig` authenticator,
+//
+//   base64(0x03 || BCS(MultiSig { sigs, bitmap: u16, multisig_pk }))
+//
+// which Sui verifies against the MultiSig address without trusting a Seal
+// policy. The BCS layout follows the TypeScript SDK: keys and signatures are
+// fixed-size arrays behind a scheme-indexed enum tag.
+
+/// Most keys a Sui MultiSig may hold.
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+const SIGNATURE_LEN: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiSigMember {
+    pub scheme: SignatureScheme,
+    pub pubkey: Vec<u8>,
+    pub weight: u8,
+}
+
+impl MultiSigMember {
+    pub fn address(&self) -> String {
+        public_key_to_sui_address(self.scheme, &self.pubkey)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiSigPublicKey {
+    pub pk_map: Vec<MultiSigMember>,
+    pub threshold: u16,
+}
+
+impl MultiSigPublicKey {
+    /// Voting members in group order, weighted as in the group; the threshold is
+    /// the quorum weight. Role thresholds and delegations have no MultiSig
+    /// equivalent, so a group using them is refused.
+    pub fn from_group(group: &DaoGroup) -> Result<Self> {
+        if !group.role_thresholds.is_empty() {
+            return Err(anyhow!("A Sui MultiSig cannot express role thresholds"));
+        }
+        if !group.delegations.is_empty() {
+            return Err(anyhow!("A Sui MultiSig cannot honor vote delegations"));
+        }
+        let pk_map = group
+            .members
+            .iter()
+            .filter(|m| m.can(Permission::Vote))
+            .map(|m| {
+                Ok(MultiSigMember {
+                    scheme: m.scheme,
+                    pubkey: hex::decode(&m.pubkey_hex)?,
+                    weight: u8::try_from(m.weight).map_err(|_| {
+                        anyhow!(
+                            "{} has weight {}; MultiSig weights are at most 255",
+                            m.name,
+                            m.weight
+                        )
+                    })?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let threshold = u16::try_from(group.required_weight())
+            .map_err(|_| anyhow!("MultiSig thresholds are at most {}", u16::MAX))?;
+        let key = Self { pk_map, threshold };
+        key.validate()?;
+        Ok(key)
+    }
+
+    /// Sui's rules: 1..=10 distinct keys, non-zero weights, and a non-zero
+    /// threshold the weights can reach.
+    pub fn validate(&self) -> Result<()> {
+        if self.pk_map.is_empty() || self.pk_map.len() > MAX_MULTISIG_SIGNERS {
+            return Err(anyhow!(
+                "A MultiSig holds 1 to {} keys, not {}",
+                MAX_MULTISIG_SIGNERS,
+                self.pk_map.len()
+            ));
+        }
+        if self.threshold == 0 || self.pk_map.iter().any(|m| m.weight == 0) {
+            return Err(anyhow!("MultiSig threshold and weights must be positive"));
+        }
+        let total: u64 = self.pk_map.iter().map(|m| u64::from(m.weight)).sum();
+        if total < u64::from(self.threshold) {
+            return Err(anyhow!(
+                "MultiSig weights total {}, below the threshold {}",
+                total,
+                self.threshold
+            ));
+        }
+        for (i, member) in self.pk_map.iter().enumerate() {
+            if member.pubkey.len() != member.scheme.pubkey_len() {
+                return Err(anyhow!("Invalid {} public key in MultiSig", member.scheme));
+            }
+            if self.pk_map[..i].iter().any(|m| m.pubkey == member.pubkey) {
+                return Err(anyhow!("Duplicate public key in MultiSig"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Blake2b256(0x03 || threshold (u16 LE) || (flag || pubkey || weight)*).
+    pub fn address(&self) -> String {
+        let mut data = vec![MULTISIG_FLAG];
+        data.extend_from_slice(&self.threshold.to_le_bytes());
+        for member in &self.pk_map {
+            data.push(member.scheme.flag());
+            data.extend_from_slice(&member.pubkey);
+            data.push(member.weight);
+        }
+        format!("0x{}", hex::encode(Blake2b256::digest(&data).digest))
+    }
+
+    fn write_bcs(&self, out: &mut Vec<u8>) {
+        out.push(self.pk_map.len() as u8);
+        for member in &self.pk_map {
+            out.push(member.scheme.flag());
+            out.extend_from_slice(&member.pubkey);
+            out.push(member.weight);
+        }
+        out.extend_from_slice(&self.threshold.to_le_bytes());
+    }
+
+    fn read_bcs(reader: &mut Reader) -> Result<Self> {
+        let count = reader.len_prefix(MAX_MULTISIG_SIGNERS)?;
+        let mut pk_map = Vec::with_capacity(count);
+        for _ in 0..count {
+            let scheme = SignatureScheme::from_flag(reader.byte()?)?;
+            pk_map.push(MultiSigMember {
+                scheme,
+                pubkey: reader.take(scheme.pubkey_len())?.to_vec(),
+                weight: reader.byte()?,
+            });
+        }
+        let threshold = u16::from_le_bytes(reader.take(2)?.try_into().expect("2 bytes"));
+        Ok(Self { pk_map, threshold })
+    }
+}
+
+/// A combined approval: one signature per set bit of `bitmap`, in key order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiSig {
+    pub sigs: Vec<(SignatureScheme, [u8; SIGNATURE_LEN])>,
+    pub bitmap: u16,
+    pub multisig_pk: MultiSigPublicKey,
+}
+
+impl MultiSig {
+    /// Combines members' serialized personal-message signatures over `message`
+    /// (keyed by Sui address). Invalid or foreign signatures are left out; fails
+    /// unless the included weight reaches the threshold.
+    pub fn combine(
+        multisig_pk: &MultiSigPublicKey,
+        message: &[u8],
+        signatures: &HashMap<String, String>,
+    ) -> Result<Self> {
+        multisig_pk.validate()?;
+        let mut multisig = Self {
+            sigs: Vec::new(),
+            bitmap: 0,
+            multisig_pk: multisig_pk.clone(),
+        };
+        for (i, member) in multisig_pk.pk_map.iter().enumerate() {
+            let address = member.address();
+            let Some(serialized) = signatures.get(&address) else {
+                continue;
+            };
+            if verify_personal_message(message, serialized).ok() != Some(address) {
+                continue;
+            }
+            let bytes =
+                Base64::decode(serialized).map_err(|_| anyhow!("Signature is not base64"))?;
+            let mut signature = [0u8; SIGNATURE_LEN];
+            signature.copy_from_slice(&bytes[1..1 + SIGNATURE_LEN]);
+            multisig.sigs.push((member.scheme, signature));
+            multisig.bitmap |= 1 << i;
+        }
+        let weight = multisig.weight();
+        if weight < u64::from(multisig_pk.threshold) {
+            return Err(anyhow!(
+                "MultiSig has {} of {} required weight",
+                weight,
+                multisig_pk.threshold
+            ));
+        }
+        Ok(multisig)
+    }
+
+    /// Total weight of the keys marked in the bitmap.
+    pub fn weight(&self) -> u64 {
+        self.signer_indices()
+            .map(|i| u64::from(self.multisig_pk.pk_map[i].weight))
+            .sum()
+    }
+
+    fn signer_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.multisig_pk.pk_map.len()).filter(|i| self.bitmap & (1 << i) != 0)
+    }
+
+    /// base64(0x03 || BCS(MultiSig)), as submitted to Sui.
+    pub fn serialize(&self) -> String {
+        let mut out = vec![MULTISIG_FLAG, self.sigs.len() as u8];
+        for (scheme, signature) in &self.sigs {
+            out.push(scheme.flag());
+            out.extend_from_slice(signature);
+        }
+        out.extend_from_slice(&self.bitmap.to_le_bytes());
+        self.multisig_pk.write_bcs(&mut out);
+        Base64::encode(out)
+    }
+
+    pub fn parse(serialized: &str) -> Result<Self> {
+        let bytes = Base64::decode(serialized).map_err(|_| anyhow!("Signature is not base64"))?;
+        let mut reader = Reader(&bytes);
+        if reader.byte()? != MULTISIG_FLAG {
+            return Err(anyhow!("Not a MultiSig signature"));
+        }
+        let count = reader.len_prefix(MAX_MULTISIG_SIGNERS)?;
+        let mut sigs = Vec::with_capacity(count);
+        for _ in 0..count {
+            let scheme = SignatureScheme::from_flag(reader.byte()?)?;
+            let signature = reader.take(SIGNATURE_LEN)?.try_into().expect("64 bytes");
+            sigs.push((scheme, signature));
+        }
+        let bitmap = u16::from_le_bytes(reader.take(2)?.try_into().expect("2 bytes"));
+        let multisig_pk = MultiSigPublicKey::read_bcs(&mut reader)?;
+        if !reader.0.is_empty() {
+            return Err(anyhow!("Trailing bytes after MultiSig"));
+        }
+        Ok(Self {
+            sigs,
+            bitmap,
+            multisig_pk,
+        })
+    }
+
+    /// Checks every signature against its key, as Sui does, and returns the
+    /// MultiSig address that approved `message`.
+    pub fn verify(&self, message: &[u8]) -> Result<String> {
+        self.multisig_pk.validate()?;
+        let indices: Vec<usize> = self.signer_indices().collect();
+        if self.bitmap >> self.multisig_pk.pk_map.len() != 0 || indices.len() != self.sigs.len() {
+            return Err(anyhow!("MultiSig bitmap does not match its signatures"));
+        }
+        for (i, (scheme, signature)) in indices.into_iter().zip(&self.sigs) {
+            let member = &self.multisig_pk.pk_map[i];
+            if *scheme != member.scheme {
+                return Err(anyhow!("MultiSig signature {} has the wrong scheme", i));
+            }
+            let single = serialize_signature(member.scheme, signature, &member.pubkey);
+            verify_personal_message(message, &single)
+                .map_err(|e| anyhow!("MultiSig signature {} is invalid: {}", i, e))?;
+        }
+        if self.weight() < u64::from(self.multisig_pk.threshold) {
+            return Err(anyhow!(
+                "MultiSig has {} of {} required weight",
+                self.weight(),
+                self.multisig_pk.threshold
+            ));
+        }
+        Ok(self.multisig_pk.address())
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(anyhow!("Truncated MultiSig"));
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// A ULEB128 vector length; every length here fits in one byte.
+    fn len_prefix(&mut self, max: usize) -> Result<usize> {
+        let len = self.byte()? as usize;
+        if len > max {
+            return Err(anyhow!(
+                "MultiSig vector of {} entries exceeds {}",
+                len,
+                max
+            ));
+        }
+        Ok(len)
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::{Result, anyhow};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use crate::audit_log::{AuditEvent, AuditLog};
+use crate::dao::{DaoGroup, MemberKey, Permission};
+use crate::sui_utils::{build_and_hash_sui_tx, verify_personal_message};
+
+// Members never sign a bare transaction digest. They sign
+//
+//   Blake2b256("SOVEREIGN_PROPOSAL_V1" || BCS(Proposal))
+//
+// which binds the payload to one DAO (its vault address), one chain, one
+// nonce and an expiry. The executor consumes the nonce in a `NonceLedger` and
+// the audit log, so a vote can neither be replayed for an identical transfer
+// nor for another DAO.
+// Guardian vetoes sign the same header under "SOVEREIGN_VETO_V1", so a vote is
+// never mistaken for a veto, and the executor authorizes signing under
+// "SOVEREIGN_EXECUTE_V1", so a published vote cannot stand in for it. The
+// proposer signs the draft under "SOVEREIGN_PROPOSE_V1", so proposing is not
+// voting; a proposer who supports the transfer votes like anyone else.
+
+const PROPOSAL_DOMAIN: &[u8] = b"SOVEREIGN_PROPOSAL_V1";
+const PROPOSE_DOMAIN: &[u8] = b"SOVEREIGN_PROPOSE_V1";
+const VETO_DOMAIN: &[u8] = b"SOVEREIGN_VETO_V1";
+const EXECUTE_DOMAIN: &[u8] = b"SOVEREIGN_EXECUTE_V1";
+
+/// Sui mainnet, in wallet-standard notation.
+pub const DEFAULT_CHAIN_ID: &str = "sui:mainnet";
+
+/// Default time members have to vote on and execute a proposal.
+pub const DEFAULT_PROPOSAL_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// The replay-protected header members vote on.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Proposal {
+    /// The DAO's vault address.
+    pub dao_id: String,
+    pub nonce: u64,
+    pub expires_at: u64,
+    pub chain_id: String,
+    /// Hex digest of the action being approved (for transfers, the Sui tx digest).
+    pub payload_digest: String,
+}
+
+impl Proposal {
+    /// The message members sign: Blake2b256(domain || BCS(proposal)).
+    pub fn digest(&self) -> Result<Vec<u8>> {
+        let mut data = PROPOSAL_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(self)?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    /// The message the proposer signs: Blake2b256(propose domain || BCS(proposal)).
+    pub fn propose_digest(&self) -> Result<Vec<u8>> {
+        let mut data = PROPOSE_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(self)?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    /// The message guardians sign to veto: Blake2b256(veto domain || BCS(proposal)).
+    pub fn veto_digest(&self) -> Result<Vec<u8>> {
+        let mut data = VETO_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(self)?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    /// The message the executor signs: Blake2b256(execute domain || BCS(proposal)).
+    pub fn execute_digest(&self) -> Result<Vec<u8>> {
+        let mut data = EXECUTE_DOMAIN.to_vec();
+        data.extend(bcs::to_bytes(self)?);
+        Ok(Blake2b256::digest(&data).digest.to_vec())
+    }
+
+    /// Rejects proposals for another DAO or chain, expired ones, and nonces
+    /// the ledger has already consumed.
+    pub fn check(
+        &self,
+        dao_id: &str,
+        chain_id: &str,
+        now: u64,
+        ledger: &NonceLedger,
+    ) -> Result<()> {
+        if self.dao_id != dao_id {
+            return Err(anyhow!(
+                "Proposal is for DAO {}, not {}",
+                self.dao_id,
+                dao_id
+            ));
+        }
+        if self.chain_id != chain_id {
+            return Err(anyhow!(
+                "Proposal is for chain {}, not {}",
+                self.chain_id,
+                chain_id
+            ));
+        }
+        if now > self.expires_at {
+            return Err(anyhow!("Proposal expired at {}", self.expires_at));
+        }
+        ledger.check_unused(&self.dao_id, self.nonce)
+    }
+}
+
+/// Persisted per-DAO nonces. Each nonce executes at most once, in any order,
+/// so executing nonce `n` leaves a pending, approved `n - 1` executable.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NonceLedger {
+    /// Highest nonce handed out, per DAO id.
+    pub issued: BTreeMap<String, u64>,
+    /// Nonces executed, per DAO id.
+    #[serde(default)]
+    pub used: BTreeMap<String, BTreeSet<u64>>,
+    /// Ledgers written before `used` kept only the highest executed nonce
+    /// (as `consumed`); every nonce up to it stays retired.
+    #[serde(
+        default,
+        alias = "consumed",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub retired_through: BTreeMap<String, u64>,
+}
+
+impl NonceLedger {
+    /// Starts empty when the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!("Failed to read nonce ledger '{}': {}", path, e)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Allocates the next nonce for `dao_id`, above every nonce already used.
+    pub fn issue(&mut self, dao_id: &str) -> u64 {
+        let floor = self
+            .used
+            .get(dao_id)
+            .and_then(|used| used.last().copied())
+            .into_iter()
+            .chain(self.retired_through.get(dao_id).copied())
+            .max()
+            .unwrap_or(0);
+        let issued = self.issued.entry(dao_id.to_string()).or_insert(0);
+        *issued = (*issued).max(floor) + 1;
+        *issued
+    }
+
+    pub fn check_unused(&self, dao_id: &str, nonce: u64) -> Result<()> {
+        if self
+            .used
+            .get(dao_id)
+            .is_some_and(|used| used.contains(&nonce))
+        {
+            return Err(anyhow!("Nonce {} was already used for {}", nonce, dao_id));
+        }
+        match self.retired_through.get(dao_id) {
+            Some(&last) if nonce <= last => Err(anyhow!(
+                "Nonce {} was retired by an older ledger (last executed nonce for {} was {})",
+                nonce,
+                dao_id,
+                last
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Marks `nonce` executed; fails if it already was.
+    pub fn consume(&mut self, dao_id: &str, nonce: u64) -> Result<()> {
+        self.check_unused(dao_id, nonce)?;
+        self.used
+            .entry(dao_id.to_string())
+            .or_default()
+            .insert(nonce);
+        Ok(())
+    }
+
+    /// Marks every nonce the audit log records as consumed, so losing the
+    /// ledger file does not re-open them.
+    pub fn absorb_audit_log(&mut self, log: &AuditLog) {
+        for entry in &log.entries {
+            if let AuditEvent::NonceConsumed { dao_id, nonce } = &entry.event {
+                self.used.entry(dao_id.clone()).or_default().insert(*nonce);
+            }
+        }
+    }
+}
+
+/// A transfer drafted by a member with the proposer role. Voters sign
+/// `header.digest()`; the TEE signs `tx_digest()`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferProposal {
+    pub header: Proposal,
+    pub recipient: String,
+    pub amount: u64,
+    /// Sui address of the proposer.
+    pub proposer: String,
+    /// Proposer's personal-message signature over `header.propose_digest()`;
+    /// not a vote.
+    pub proposer_signature: String,
+}
+
+impl TransferProposal {
+    pub fn create(
+        group: &DaoGroup,
+        proposer: &MemberKey,
+        recipient: &str,
+        amount: u64,
+        chain_id: &str,
+        nonce: u64,
+        expires_at: u64,
+    ) -> Result<Self> {
+        let vault_address = group
+            .vault_address
+            .clone()
+            .ok_or(anyhow!("The group has no vault address; launch first"))?;
+        let address = proposer.member()?.address;
+        group.require_permission(&address, Permission::Propose)?;
+
+        let header = Proposal {
+            payload_digest: hex::encode(build_and_hash_sui_tx(&vault_address, recipient, amount)?),
+            dao_id: vault_address,
+            nonce,
+            expires_at,
+            chain_id: chain_id.to_string(),
+        };
+        Ok(Self {
+            proposer_signature: proposer.sign_personal_message(&header.propose_digest()?)?,
+            header,
+            recipient: recipient.to_string(),
+            amount,
+            proposer: address,
+        })
+    }
+
+    /// The transaction digest the TEE signs.
+    pub fn tx_digest(&self) -> Result<Vec<u8>> {
+        build_and_hash_sui_tx(&self.header.dao_id, &self.recipient, self.amount)
+    }
+
+    /// The message members vote on.
+    pub fn vote_digest(&self) -> Result<Vec<u8>> {
+        self.header.digest()
+    }
+
+    /// The message guardians sign to veto it.
+    pub fn veto_digest(&self) -> Result<Vec<u8>> {
+        self.header.veto_digest()
+    }
+
+    /// The message the executor signs to run it.
+    pub fn execute_digest(&self) -> Result<Vec<u8>> {
+        self.header.execute_digest()
+    }
+
+    /// Checks `signature` is `executor`'s authorization to execute, and that
+    /// `executor` is a member who may execute.
+    pub fn verify_execution(
+        &self,
+        group: &DaoGroup,
+        executor: &str,
+        signature: &str,
+    ) -> Result<()> {
+        group.require_permission(executor, Permission::Execute)?;
+        let signer = verify_personal_message(&self.execute_digest()?, signature)
+            .map_err(|e| anyhow!("Invalid executor signature: {}", e))?;
+        if signer != executor {
+            return Err(anyhow!("Execution was not authorized by {}", executor));
+        }
+        Ok(())
+    }
+
+    /// Checks the proposal is live for this group and chain, its header matches
+    /// the transfer, and it was drafted by a member who may propose.
+    pub fn verify(
+        &self,
+        group: &DaoGroup,
+        chain_id: &str,
+        now: u64,
+        ledger: &NonceLedger,
+    ) -> Result<()> {
+        let vault_address = group
+            .vault_address
+            .as_deref()
+            .ok_or(anyhow!("The group has no vault address; launch first"))?;
+        self.header.check(vault_address, chain_id, now, ledger)?;
+        if self.header.payload_digest != hex::encode(self.tx_digest()?) {
+            return Err(anyhow!("Proposal header does not match the transfer"));
+        }
+
+        group.require_permission(&self.proposer, Permission::Propose)?;
+        let signer =
+            verify_personal_message(&self.header.propose_digest()?, &self.proposer_signature)
+                .map_err(|e| anyhow!("Invalid proposer signature: {}", e))?;
+        if signer != self.proposer {
+            return Err(anyhow!("Proposal was not signed by its proposer"));
+        }
+        Ok(())
+    }
+}
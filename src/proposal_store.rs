@@ -0,0 +1,382 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use crate::dao::{DaoGroup, Permission, ProposalKind};
+use crate::proposal::{NonceLedger, TransferProposal};
+use crate::sui_utils::verify_personal_message;
+
+// Local mirror of the on-chain `sovereign_dao::dao::Proposal` lifecycle:
+//
+//   Active --votes reach quorum--> Passed --timelock elapsed, signed by the TEE--> Executed
+//      \                             /
+//       `--expired, superseded or vetoed--> Failed
+//
+// A passed proposal waits out the group's timelock; guardians may veto it
+// until then.
+//
+// Status codes are the Move ones and are stored as numbers.
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum ProposalStatus {
+    Active = 0,
+    Passed = 1,
+    Failed = 2,
+    Executed = 3,
+}
+
+impl From<ProposalStatus> for u8 {
+    fn from(status: ProposalStatus) -> u8 {
+        status as u8
+    }
+}
+
+impl TryFrom<u8> for ProposalStatus {
+    type Error = anyhow::Error;
+
+    fn try_from(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(ProposalStatus::Active),
+            1 => Ok(ProposalStatus::Passed),
+            2 => Ok(ProposalStatus::Failed),
+            3 => Ok(ProposalStatus::Executed),
+            _ => Err(anyhow!("Unknown proposal status code {}", code)),
+        }
+    }
+}
+
+impl std::fmt::Display for ProposalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalStatus::Active => write!(f, "active"),
+            ProposalStatus::Passed => write!(f, "passed"),
+            ProposalStatus::Failed => write!(f, "failed"),
+            ProposalStatus::Executed => write!(f, "executed"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProposalStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "active" => Ok(ProposalStatus::Active),
+            "passed" => Ok(ProposalStatus::Passed),
+            "failed" => Ok(ProposalStatus::Failed),
+            "executed" => Ok(ProposalStatus::Executed),
+            _ => s.parse::<u8>()?.try_into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VoteRecord {
+    pub member: String,
+    /// Personal-message signature over the proposal's vote digest.
+    pub signature: String,
+    pub cast_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExecutionRecord {
+    /// Sui address of the executor.
+    pub executor: String,
+    /// Executor's personal-message signature over the proposal's execute digest.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub authorization: String,
+    pub executed_at: u64,
+    pub tx_digest: String,
+    /// The vault's signature over `tx_digest`.
+    pub signature: String,
+    /// Serialized Sui MultiSig over the vote digest, in MultiSig governance mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProposalRecord {
+    pub proposal: TransferProposal,
+    pub status: ProposalStatus,
+    pub created_at: u64,
+    /// Votes keyed by Sui address.
+    pub votes: BTreeMap<String, VoteRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution: Option<ExecutionRecord>,
+    /// Earliest execution time, set when the proposal passes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable_at: Option<u64>,
+    /// Guardian vetoes keyed by Sui address, signed over the veto digest.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vetoes: BTreeMap<String, VoteRecord>,
+}
+
+impl ProposalRecord {
+    /// The votes in the form `DaoGroup::verify_proposal` takes.
+    pub fn signatures(&self) -> HashMap<String, String> {
+        self.votes
+            .iter()
+            .map(|(address, vote)| (address.clone(), vote.signature.clone()))
+            .collect()
+    }
+
+    /// Names of current guardians whose veto signature is valid.
+    pub fn vetoers(&self, group: &DaoGroup) -> Result<Vec<String>> {
+        let veto_digest = self.proposal.veto_digest()?;
+        Ok(self
+            .vetoes
+            .iter()
+            .filter_map(|(address, veto)| {
+                let member = group.require_permission(address, Permission::Veto).ok()?;
+                (verify_personal_message(&veto_digest, &veto.signature).ok()? == *address)
+                    .then(|| member.name.clone())
+            })
+            .collect())
+    }
+
+    /// Fails unless the proposal passed, its timelock elapsed by `now` and the
+    /// guardians have not reached the veto threshold.
+    pub fn check_executable(&self, group: &DaoGroup, now: u64) -> Result<()> {
+        let id = self.proposal.header.nonce;
+        if self.status != ProposalStatus::Passed {
+            return Err(anyhow!("Proposal #{} is {}, not passed", id, self.status));
+        }
+        let vetoers = self.vetoers(group)?;
+        if vetoers.len() >= group.timelock.veto_threshold {
+            return Err(anyhow!(
+                "Proposal #{} was vetoed by {}",
+                id,
+                vetoers.join(", ")
+            ));
+        }
+        if let Some(executable_at) = self.executable_at {
+            if now < executable_at {
+                return Err(anyhow!(
+                    "Proposal #{} is timelocked until {} ({}s left)",
+                    id,
+                    executable_at,
+                    executable_at - now
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every proposal of one group, keyed by nonce.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ProposalStore {
+    pub proposals: BTreeMap<u64, ProposalRecord>,
+}
+
+impl ProposalStore {
+    /// Starts empty when the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!("Failed to read proposal store '{}': {}", path, e)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, id: u64) -> Result<&ProposalRecord> {
+        self.proposals
+            .get(&id)
+            .ok_or(anyhow!("Proposal #{} not found", id))
+    }
+
+    fn get_mut(&mut self, id: u64) -> Result<&mut ProposalRecord> {
+        self.proposals
+            .get_mut(&id)
+            .ok_or(anyhow!("Proposal #{} not found", id))
+    }
+
+    /// Stores a new proposal as Active; its id is the header nonce.
+    pub fn add(&mut self, proposal: TransferProposal, now: u64) -> Result<u64> {
+        let id = proposal.header.nonce;
+        if self.proposals.contains_key(&id) {
+            return Err(anyhow!("Proposal #{} already exists", id));
+        }
+        self.proposals.insert(
+            id,
+            ProposalRecord {
+                proposal,
+                status: ProposalStatus::Active,
+                created_at: now,
+                votes: BTreeMap::new(),
+                failure: None,
+                execution: None,
+                executable_at: None,
+                vetoes: BTreeMap::new(),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Records one vote and moves the proposal to Passed once the group's
+    /// quorum (weight and role thresholds) is met. A passed proposal still takes
+    /// votes until it executes, for transfers the spending policy wants more
+    /// approvals on.
+    pub fn record_vote(
+        &mut self,
+        group: &DaoGroup,
+        id: u64,
+        address: &str,
+        signature: &str,
+        now: u64,
+    ) -> Result<ProposalStatus> {
+        let record = self.get_mut(id)?;
+        if !matches!(
+            record.status,
+            ProposalStatus::Active | ProposalStatus::Passed
+        ) {
+            return Err(anyhow!(
+                "Proposal #{} is {}, not open for votes",
+                id,
+                record.status
+            ));
+        }
+        let member = group.require_permission(address, Permission::Vote)?;
+        if record.votes.contains_key(address) {
+            return Err(anyhow!("{} already voted on proposal #{}", member.name, id));
+        }
+        let vote_digest = record.proposal.vote_digest()?;
+        if verify_personal_message(&vote_digest, signature)? != address {
+            return Err(anyhow!("Vote is not signed by {}", member.name));
+        }
+
+        record.votes.insert(
+            address.to_string(),
+            VoteRecord {
+                member: member.name.clone(),
+                signature: signature.to_string(),
+                cast_at: now,
+            },
+        );
+        if group
+            .verify_proposal(
+                &vote_digest,
+                &record.signatures(),
+                ProposalKind::Transfer,
+                now,
+            )
+            .passed()
+            && record.status == ProposalStatus::Active
+        {
+            record.status = ProposalStatus::Passed;
+            record.executable_at = Some(now.saturating_add(group.timelock.delay_secs));
+        }
+        Ok(record.status)
+    }
+
+    /// For approvals made outside the vote ledger, such as NFT shard holders
+    /// presenting their shards. The timelock starts at `now` unless the
+    /// proposal already passed.
+    pub fn mark_passed(&mut self, group: &DaoGroup, id: u64, now: u64) -> Result<()> {
+        let record = self.get_mut(id)?;
+        match record.status {
+            ProposalStatus::Active | ProposalStatus::Passed => {
+                record.status = ProposalStatus::Passed;
+                record
+                    .executable_at
+                    .get_or_insert(now.saturating_add(group.timelock.delay_secs));
+                Ok(())
+            }
+            status => Err(anyhow!("Proposal #{} is {}", id, status)),
+        }
+    }
+
+    /// Records a guardian's veto while the timelock runs. Reaching the group's
+    /// veto threshold fails the proposal.
+    pub fn record_veto(
+        &mut self,
+        group: &DaoGroup,
+        id: u64,
+        address: &str,
+        signature: &str,
+        now: u64,
+    ) -> Result<ProposalStatus> {
+        let record = self.get_mut(id)?;
+        if record.status != ProposalStatus::Passed {
+            return Err(anyhow!(
+                "Proposal #{} is {}; only passed proposals can be vetoed",
+                id,
+                record.status
+            ));
+        }
+        if record.executable_at.is_none_or(|at| now >= at) {
+            return Err(anyhow!("The veto window of proposal #{} has closed", id));
+        }
+        let member = group.require_permission(address, Permission::Veto)?;
+        if record.vetoes.contains_key(address) {
+            return Err(anyhow!("{} already vetoed proposal #{}", member.name, id));
+        }
+        if verify_personal_message(&record.proposal.veto_digest()?, signature)? != address {
+            return Err(anyhow!("Veto is not signed by {}", member.name));
+        }
+
+        record.vetoes.insert(
+            address.to_string(),
+            VoteRecord {
+                member: member.name.clone(),
+                signature: signature.to_string(),
+                cast_at: now,
+            },
+        );
+        let vetoers = record.vetoers(group)?;
+        if vetoers.len() >= group.timelock.veto_threshold {
+            record.status = ProposalStatus::Failed;
+            record.failure = Some(format!("vetoed by {}", vetoers.join(", ")));
+        }
+        Ok(record.status)
+    }
+
+    pub fn record_execution(&mut self, id: u64, execution: ExecutionRecord) -> Result<()> {
+        let record = self.get_mut(id)?;
+        if record.status != ProposalStatus::Passed {
+            return Err(anyhow!("Proposal #{} is {}, not passed", id, record.status));
+        }
+        record.status = ProposalStatus::Executed;
+        record.execution = Some(execution);
+        Ok(())
+    }
+
+    /// Fails open proposals that expired or whose nonce was superseded.
+    /// Returns the ids that changed.
+    pub fn sweep(&mut self, now: u64, ledger: &NonceLedger) -> Vec<u64> {
+        let mut failed = Vec::new();
+        for (id, record) in self.proposals.iter_mut() {
+            if !matches!(
+                record.status,
+                ProposalStatus::Active | ProposalStatus::Passed
+            ) {
+                continue;
+            }
+            let header = &record.proposal.header;
+            let reason = if now > header.expires_at {
+                format!("expired at {}", header.expires_at)
+            } else if let Err(e) = ledger.check_unused(&header.dao_id, header.nonce) {
+                e.to_string()
+            } else {
+                continue;
+            };
+            record.status = ProposalStatus::Failed;
+            record.failure = Some(reason);
+            failed.push(*id);
+        }
+        failed
+    }
+
+    pub fn with_status(&self, status: ProposalStatus) -> impl Iterator<Item = &ProposalRecord> {
+        self.proposals.values().filter(move |r| r.status == status)
+    }
+}
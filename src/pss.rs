@@ -1,53 +1,193 @@
 use anyhow::{Result, anyhow};
-use dwallet_mpc_types::dwallet_mpc::DWalletCurve;
+use group::Samplable;
 use group::secp256k1::Scalar as SecpScalar;
 use group::secp256k1::scalar::PublicParameters;
-use group::{OsCsRng, Samplable};
+use rand_core::{CryptoRng, OsRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::scalar_utils::{bytes_to_scalar, scalar_to_bytes};
+use crate::secret::{SecretBytes, SecretScalar};
+use crate::sharding::{Shard, recover_secret, split_secret_with_rng};
+use crate::share_codec::Share;
 
 // For mock signing and verification
-use k256::FieldBytes;
 use k256::ecdsa::signature::{Signer, Verifier};
 use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
 
+pub const DAO_PARTY: &str = "dao";
+pub const TEE_PARTY: &str = "tee";
+
 pub struct RefreshedShares {
-    pub new_dao_share: Vec<u8>,
-    pub new_tee_share: Vec<u8>,
+    pub new_dao_share: SecretBytes,
+    pub new_tee_share: SecretBytes,
 }
 
+/// Result of a refresh under the NftSharding strategy.
+pub struct ShardedRefresh {
+    pub new_dao_shards: Vec<Shard>,
+    pub new_tee_share: SecretBytes,
+}
+
+/// One additive share of the vault key, held by a named party
+/// (e.g. "dao", "tee", "compliance"). The vault key is the sum of all shares.
+pub struct PartyShare {
+    pub party: String,
+    pub share: SecretBytes,
+}
+
+/// Two-party refresh of the DAO/TEE pair. Wrapper over `perform_additive_refresh`.
 pub fn perform_pss_refresh(
     curve: u32,
     dao_share_bytes: &[u8],
     tee_share_bytes: &[u8],
 ) -> Result<RefreshedShares> {
+    perform_pss_refresh_with_rng(curve, dao_share_bytes, tee_share_bytes, &mut OsRng)
+}
+
+pub fn perform_pss_refresh_with_rng<R: CryptoRng + RngCore>(
+    curve: u32,
+    dao_share_bytes: &[u8],
+    tee_share_bytes: &[u8],
+    rng: &mut R,
+) -> Result<RefreshedShares> {
+    let shares = [
+        PartyShare {
+            party: DAO_PARTY.to_string(),
+            share: SecretBytes::new(dao_share_bytes.to_vec()),
+        },
+        PartyShare {
+            party: TEE_PARTY.to_string(),
+            share: SecretBytes::new(tee_share_bytes.to_vec()),
+        },
+    ];
+    let mut refreshed = perform_additive_refresh_with_rng(curve, &shares, rng)?.into_iter();
+    let (Some(dao), Some(tee)) = (refreshed.next(), refreshed.next()) else {
+        return Err(anyhow!("PSS refresh lost a share"));
+    };
+
+    Ok(RefreshedShares {
+        new_dao_share: dao.share,
+        new_tee_share: tee.share,
+    })
+}
+
+/// n-of-n refresh: every party gets a fresh delta and the deltas sum to zero,
+/// so the vault key is unchanged while every old share is invalidated.
+pub fn perform_additive_refresh(curve: u32, shares: &[PartyShare]) -> Result<Vec<PartyShare>> {
+    perform_additive_refresh_with_rng(curve, shares, &mut OsRng)
+}
+
+pub fn perform_additive_refresh_with_rng<R: CryptoRng + RngCore>(
+    curve: u32,
+    shares: &[PartyShare],
+    rng: &mut R,
+) -> Result<Vec<PartyShare>> {
     match curve {
-        0 => refresh_secp256k1(dao_share_bytes, tee_share_bytes),
+        0 => refresh_secp256k1(shares, rng),
         _ => Err(anyhow!("Curve not supported for PSS yet")),
     }
 }
 
-fn refresh_secp256k1(dao_share_bytes: &[u8], tee_share_bytes: &[u8]) -> Result<RefreshedShares> {
-    let s_dao: SecpScalar = bcs::from_bytes(dao_share_bytes)
-        .map_err(|e| anyhow!("Failed to deserialize DAO share: {}", e))?;
+fn refresh_secp256k1<R: CryptoRng + RngCore>(
+    shares: &[PartyShare],
+    rng: &mut R,
+) -> Result<Vec<PartyShare>> {
+    check_parties(shares.iter().map(|s| s.party.as_str()))?;
 
-    let s_tee: SecpScalar = bcs::from_bytes(tee_share_bytes)
-        .map_err(|e| anyhow!("Failed to deserialize TEE share: {}", e))?;
+    let mut scalars = Zeroizing::new(Vec::with_capacity(shares.len()));
+    for s in shares {
+        let scalar = bytes_to_scalar(s.share.expose_secret())
+            .map_err(|e| anyhow!("Failed to deserialize {} share: {}", s.party, e))?;
+        scalars.push(*scalar.expose_secret());
+    }
+
+    // The last party absorbs the negated sum of everyone else's delta.
+    let last = scalars.len() - 1;
+    for i in 0..last {
+        let alpha = *sample_share(rng)?.scalar();
+        scalars[i] += alpha;
+        scalars[last] -= alpha;
+    }
 
-    let pp = PublicParameters::default();
-    // Use OsCsRng from the group crate to ensure trait compatibility
-    let alpha = SecpScalar::sample(&pp, &mut OsCsRng)
-        .map_err(|e| anyhow!("Failed to sample alpha: {}", e))?;
+    shares
+        .iter()
+        .zip(scalars.iter())
+        .map(|(s, scalar)| {
+            let share = scalar_to_bytes(scalar)
+                .map_err(|e| anyhow!("Failed to serialize new {} share: {}", s.party, e))?;
+            Ok(PartyShare {
+                party: s.party.clone(),
+                share,
+            })
+        })
+        .collect()
+}
 
-    let s_dao_new = s_dao + &alpha;
-    let s_tee_new = s_tee - &alpha;
+fn check_parties<'a>(parties: impl Iterator<Item = &'a str>) -> Result<()> {
+    let mut seen = std::collections::HashSet::new();
+    for party in parties {
+        if party.is_empty() {
+            return Err(anyhow!("Party name must not be empty"));
+        }
+        if !seen.insert(party) {
+            return Err(anyhow!("Duplicate party '{}'", party));
+        }
+    }
+    if seen.len() < 2 {
+        return Err(anyhow!("Additive sharing needs at least two parties"));
+    }
+    Ok(())
+}
 
-    let new_dao_bytes = bcs::to_bytes(&s_dao_new)
-        .map_err(|e| anyhow!("Failed to serialize new DAO share: {}", e))?;
+/// PSS refresh when the DAO side is held as Shamir shards.
+/// The TEE share is re-randomized and the DAO secret is re-split into `total` fresh
+/// shards carrying the complementary delta, so every old shard is invalidated.
+pub fn perform_sharded_refresh(
+    curve: u32,
+    tee_share_bytes: &[u8],
+    dao_shards: &[Shard],
+    threshold: usize,
+    total: usize,
+) -> Result<ShardedRefresh> {
+    perform_sharded_refresh_with_rng(
+        curve,
+        tee_share_bytes,
+        dao_shards,
+        threshold,
+        total,
+        &mut OsRng,
+    )
+}
 
-    let new_tee_bytes = bcs::to_bytes(&s_tee_new)
+pub fn perform_sharded_refresh_with_rng<R: CryptoRng + RngCore>(
+    curve: u32,
+    tee_share_bytes: &[u8],
+    dao_shards: &[Shard],
+    threshold: usize,
+    total: usize,
+    rng: &mut R,
+) -> Result<ShardedRefresh> {
+    if curve != 0 {
+        return Err(anyhow!("Curve not supported for PSS yet"));
+    }
+    if dao_shards.len() < threshold {
+        return Err(anyhow!(
+            "Not enough shards! Need {}, got {}",
+            threshold,
+            dao_shards.len()
+        ));
+    }
+
+    let s_tee = bytes_to_scalar(tee_share_bytes)
+        .map_err(|e| anyhow!("Failed to deserialize TEE share: {}", e))?;
+    let alpha = *sample_share(rng)?.scalar();
+
+    let new_tee_bytes = scalar_to_bytes(&(*s_tee.expose_secret() - alpha))
         .map_err(|e| anyhow!("Failed to serialize new TEE share: {}", e))?;
+    let s_dao_new = SecretScalar::new(*recover_secret(dao_shards)?.expose_secret() + alpha);
 
-    Ok(RefreshedShares {
-        new_dao_share: new_dao_bytes,
+    Ok(ShardedRefresh {
+        new_dao_shards: split_secret_with_rng(&s_dao_new, threshold, total, rng),
         new_tee_share: new_tee_bytes,
     })
 }
@@ -61,48 +201,13 @@ pub fn mock_sign_and_verify(
     message: &[u8],
 ) -> Result<(String, String)> {
     // 1. Reconstruct Private Key (s = s1 + s2)
-    let s_dao: SecpScalar = bcs::from_bytes(dao_share_bytes)?;
-    let s_tee: SecpScalar = bcs::from_bytes(tee_share_bytes)?;
-
-    let private_key_scalar = s_dao + s_tee;
-
-    // Convert Scalar to bytes via BCS
-    let mut key_bytes = bcs::to_bytes(&private_key_scalar)?;
-
-    // Debug: Print length if not 32
-    if key_bytes.len() != 32 {
-        eprintln!("DEBUG: BCS Scalar Bytes Len: {}", key_bytes.len());
-        // If 33 bytes, it might be [len, data...] or [data..., extra]
-        // But for fixed size structs, BCS shouldn't add len.
-        // Unless it's encoding an Enum or specific internal structure.
-
-        // Let's try to strip the first byte if it is 33 bytes?
-        // Wait, k256 private keys are 32 bytes.
-        // If we have 33 bytes, maybe it's just padding or a tag.
-
-        // Strategy: Force fit to 32 bytes.
-        // If > 32, take last 32? Or first 32?
-        // Assuming LE encoding, and extra bytes might be high bits or overflow protection.
-
-        // Let's try taking the *last* 32 bytes if we assume it's BE with a leading 0?
-        // Or if it's LE, first 32 bytes?
-
-        // Given the previous panic was `assertion left == right failed: left: 33, right: 32`
-        // inside `SigningKey::from_bytes`, which strictly requires 32 bytes.
-
-        // Let's assume it's a fixed size 32 byte scalar but maybe BCS adds something?
-        // Actually, dwallet-mpc Scalar might be 4 limbs of u64 + extra stuff?
-
-        // FIX: Let's try to just take the first 32 bytes for now.
-        // If it's LE, that's the data.
-        key_bytes.resize(32, 0);
-    }
-
-    // Hack: Assuming LE because most Rust crypto libs are LE.
-    // k256 SigningKey needs BE.
-    key_bytes.reverse();
+    let s_dao = bytes_to_scalar(dao_share_bytes)
+        .map_err(|e| anyhow!("Failed to deserialize DAO share: {}", e))?;
+    let s_tee = bytes_to_scalar(tee_share_bytes)
+        .map_err(|e| anyhow!("Failed to deserialize TEE share: {}", e))?;
 
-    let signing_key = SigningKey::from_bytes(&FieldBytes::from_slice(&key_bytes))
+    let key = Zeroizing::new(s_dao.expose_secret() + s_tee.expose_secret());
+    let signing_key = SigningKey::from_bytes(&key.to_bytes())
         .map_err(|e| anyhow!("Invalid private key reconstruction: {}", e))?;
 
     // 2. Derive Public Key
@@ -121,12 +226,66 @@ pub fn mock_sign_and_verify(
     Ok((sig_hex, pubkey_hex))
 }
 
-pub fn generate_initial_shares() -> Result<(Vec<u8>, Vec<u8>)> {
-    let pp = PublicParameters::default();
-    let s_dao = SecpScalar::sample(&pp, &mut OsCsRng)
-        .map_err(|e| anyhow!("Failed to sample DAO share: {}", e))?;
-    let s_tee = SecpScalar::sample(&pp, &mut OsCsRng)
-        .map_err(|e| anyhow!("Failed to sample TEE share: {}", e))?;
+/// Two-party genesis for the DAO/TEE pair. Wrapper over `generate_additive_shares`.
+pub fn generate_initial_shares() -> Result<(SecretBytes, SecretBytes)> {
+    generate_initial_shares_with_rng(&mut OsRng)
+}
+
+pub fn generate_initial_shares_with_rng<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<(SecretBytes, SecretBytes)> {
+    let mut shares = generate_additive_shares_with_rng(&[DAO_PARTY, TEE_PARTY], rng)?.into_iter();
+    let (Some(dao), Some(tee)) = (shares.next(), shares.next()) else {
+        return Err(anyhow!("Share generation lost a share"));
+    };
+    Ok((dao.share, tee.share))
+}
+
+/// Adopts an existing vault key: the TEE share is fresh randomness and the DAO
+/// share is the complement, so `s_dao + s_tee` equals the imported key.
+pub fn split_existing_key(key: &SecretScalar) -> Result<(SecretBytes, SecretBytes)> {
+    split_existing_key_with_rng(key, &mut OsRng)
+}
+
+pub fn split_existing_key_with_rng<R: CryptoRng + RngCore>(
+    key: &SecretScalar,
+    rng: &mut R,
+) -> Result<(SecretBytes, SecretBytes)> {
+    let s_tee = sample_share(rng).map_err(|e| anyhow!("Failed to sample TEE share: {}", e))?;
+    let s_dao = Zeroizing::new(key.expose_secret() - s_tee.scalar());
+    Ok((
+        scalar_to_bytes(&s_dao).map_err(|e| anyhow!("Failed to serialize DAO share: {}", e))?,
+        SecretBytes::new(s_tee.encode().to_vec()),
+    ))
+}
+
+/// Samples an independent additive share for each named party.
+pub fn generate_additive_shares(parties: &[&str]) -> Result<Vec<PartyShare>> {
+    generate_additive_shares_with_rng(parties, &mut OsRng)
+}
+
+pub fn generate_additive_shares_with_rng<R: CryptoRng + RngCore>(
+    parties: &[&str],
+    rng: &mut R,
+) -> Result<Vec<PartyShare>> {
+    check_parties(parties.iter().copied())?;
+
+    parties
+        .iter()
+        .map(|party| {
+            let s = sample_share(rng)
+                .map_err(|e| anyhow!("Failed to sample {} share: {}", party, e))?;
+            Ok(PartyShare {
+                party: party.to_string(),
+                share: SecretBytes::new(s.encode().to_vec()),
+            })
+        })
+        .collect()
+}
 
-    Ok((bcs::to_bytes(&s_dao)?, bcs::to_bytes(&s_tee)?))
+/// Samples with the `group` crate, so shares match what the MPC stack produces.
+fn sample_share<R: CryptoRng + RngCore>(rng: &mut R) -> Result<Share> {
+    let s = SecpScalar::sample(&PublicParameters::default(), rng)
+        .map_err(|e| anyhow!("Failed to sample scalar: {}", e))?;
+    Ok(Share::from_group(&s)?)
 }
@@ -0,0 +1,148 @@
+use crate::pss::{RefreshedShares, perform_pss_refresh};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// When a proactive refresh must happen. Every trigger is optional;
+/// a refresh is due as soon as any enabled trigger fires.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RefreshPolicy {
+    /// Refresh after this many signatures under the current shares.
+    pub max_signatures: Option<u64>,
+    /// Refresh once this many seconds have passed since the last refresh.
+    pub max_interval_secs: Option<u64>,
+    /// Refresh whenever the DAO membership (or threshold) changes.
+    pub on_membership_change: bool,
+}
+
+/// Persisted bookkeeping of the last refresh.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RefreshState {
+    pub epoch: u64,
+    pub last_refresh_at: u64,
+    pub signatures_since_refresh: u64,
+    pub membership_digest: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshReason {
+    SignatureLimit,
+    IntervalElapsed,
+    MembershipChanged,
+}
+
+impl std::fmt::Display for RefreshReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefreshReason::SignatureLimit => write!(f, "signature limit reached"),
+            RefreshReason::IntervalElapsed => write!(f, "refresh interval elapsed"),
+            RefreshReason::MembershipChanged => write!(f, "membership changed"),
+        }
+    }
+}
+
+/// Drives `perform_pss_refresh` according to a `RefreshPolicy`. NFT shards are
+/// never all present at once, so for them the scheduler only reports that a
+/// refresh is due and `group-refresh` does it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RefreshScheduler {
+    pub policy: RefreshPolicy,
+    pub state: RefreshState,
+}
+
+impl RefreshScheduler {
+    /// Starts epoch 0 at `now` for the given membership.
+    pub fn new(policy: RefreshPolicy, now: u64, membership_digest: &str) -> Self {
+        Self {
+            policy,
+            state: RefreshState {
+                epoch: 0,
+                last_refresh_at: now,
+                signatures_since_refresh: 0,
+                membership_digest: membership_digest.to_string(),
+            },
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read refresh state '{}': {}", path, e))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn record_signature(&mut self) {
+        self.state.signatures_since_refresh += 1;
+    }
+
+    /// Returns the first trigger that makes a refresh due, if any.
+    pub fn due(&self, now: u64, membership_digest: &str) -> Option<RefreshReason> {
+        if self.policy.on_membership_change && self.state.membership_digest != membership_digest {
+            return Some(RefreshReason::MembershipChanged);
+        }
+        if let Some(max) = self.policy.max_signatures {
+            if self.state.signatures_since_refresh >= max {
+                return Some(RefreshReason::SignatureLimit);
+            }
+        }
+        if let Some(due_at) = self.next_due_at() {
+            if now >= due_at {
+                return Some(RefreshReason::IntervalElapsed);
+            }
+        }
+        None
+    }
+
+    /// Unix time at which the interval trigger fires, if enabled.
+    pub fn next_due_at(&self) -> Option<u64> {
+        self.policy
+            .max_interval_secs
+            .map(|interval| self.state.last_refresh_at.saturating_add(interval))
+    }
+
+    /// Signatures left before the signature trigger fires, if enabled.
+    pub fn signatures_remaining(&self) -> Option<u64> {
+        self.policy
+            .max_signatures
+            .map(|max| max.saturating_sub(self.state.signatures_since_refresh))
+    }
+
+    /// Starts a new epoch. Call after the refreshed shares are persisted.
+    pub fn mark_refreshed(&mut self, now: u64, membership_digest: &str) {
+        self.state.epoch += 1;
+        self.state.last_refresh_at = now;
+        self.state.signatures_since_refresh = 0;
+        self.state.membership_digest = membership_digest.to_string();
+    }
+
+    /// Re-randomizes the additive DAO/TEE pair if a refresh is due. The epoch
+    /// does not move; call `mark_refreshed` once the new shares are persisted.
+    pub fn refresh_additive_if_due(
+        &self,
+        curve: u32,
+        dao_share_bytes: &[u8],
+        tee_share_bytes: &[u8],
+        now: u64,
+        membership_digest: &str,
+    ) -> Result<Option<(RefreshReason, RefreshedShares)>> {
+        let Some(reason) = self.due(now, membership_digest) else {
+            return Ok(None);
+        };
+        let refreshed = perform_pss_refresh(curve, dao_share_bytes, tee_share_bytes)?;
+        Ok(Some((reason, refreshed)))
+    }
+}
+
+/// Fails rather than report 0 when the clock is before the Unix epoch, which
+/// would make every expiry and refresh interval meaningless.
+pub fn unix_now() -> Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| anyhow!("System clock is before the Unix epoch: {}", e))?
+        .as_secs())
+}
@@ -1,26 +1,33 @@
 use anyhow::{Result, anyhow};
-use k256::FieldBytes;
 use k256::Scalar;
-use k256::elliptic_curve::PrimeField; // Trait required for from_repr
+use zeroize::Zeroizing;
 
-// Helper to convert the opaque BCS bytes (33 bytes) into a usable Scalar
-// This mimics the logic we used in `mock_sign_and_verify`
-pub fn bytes_to_scalar(bytes: &[u8]) -> Result<Scalar> {
-    let mut key_bytes = bytes.to_vec();
+use crate::secret::{SecretBytes, SecretScalar};
+use crate::share_codec::{SCALAR_LEN, Share, scalar_from_be_bytes};
 
-    // 1. Truncate if necessary (BCS might add metadata or it's a 33-byte serialization)
-    if key_bytes.len() > 32 {
-        key_bytes.resize(32, 0);
-    }
+// Byte-level helpers over the canonical share format; see `share_codec`.
+
+/// Decode a BCS share (33 bytes) into a usable Scalar.
+pub fn bytes_to_scalar(bytes: &[u8]) -> Result<SecretScalar> {
+    Ok(Share::decode(bytes)?.into_secret())
+}
 
-    // 2. Reverse endianness (dwallet-mpc uses LE, k256 uses BE)
-    key_bytes.reverse();
+/// Encode a Scalar as a BCS share, the inverse of `bytes_to_scalar`.
+pub fn scalar_to_bytes(scalar: &Scalar) -> Result<SecretBytes> {
+    Ok(SecretBytes::new(
+        Share::from_scalar(*scalar).encode().to_vec(),
+    ))
+}
 
-    // Use Option wrapper
-    let scalar_opt = Scalar::from_repr(*FieldBytes::from_slice(&key_bytes));
-    if bool::from(scalar_opt.is_some()) {
-        Ok(scalar_opt.unwrap())
-    } else {
-        Err(anyhow!("Invalid scalar encoding"))
+/// How builds before the share codec read a share: the first 32 bytes,
+/// reversed, as a big-endian scalar. Only `share-migrate` uses this, to carry
+/// files written back then over to the canonical encoding without changing
+/// the vault address they control.
+pub fn legacy_bytes_to_scalar(bytes: &[u8]) -> Result<SecretScalar> {
+    if bytes.len() < SCALAR_LEN {
+        return Err(anyhow!("Legacy share is too short: {} bytes", bytes.len()));
     }
+    let mut repr = Zeroizing::new(bytes[..SCALAR_LEN].to_vec());
+    repr.reverse();
+    Ok(SecretScalar::new(scalar_from_be_bytes(&repr)?))
 }
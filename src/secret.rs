@@ -0,0 +1,153 @@
+use k256::Scalar;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
+
+// Wrappers for private material: shares, shards and member keys.
+//
+// Each type wipes its contents on drop, prints as `[REDACTED]`, and has no
+// `Clone`; copying a secret has to be spelled out with `duplicate()`.
+// The inner value is only reachable through `expose_secret()`.
+
+macro_rules! redacted_fmt {
+    ($ty:ident) => {
+        impl std::fmt::Debug for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}([REDACTED])", stringify!($ty))
+            }
+        }
+
+        impl std::fmt::Display for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("[REDACTED]")
+            }
+        }
+    };
+}
+
+/// Secret byte string, e.g. an encoded share or a decryption key.
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Explicit copy; both copies are wiped independently.
+    pub fn duplicate(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+redacted_fmt!(SecretBytes);
+
+// Serialized exactly like the `Vec<u8>` it replaces, so stored files keep their format.
+impl Serialize for SecretBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Secret string, e.g. a hex-encoded private key.
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    pub fn duplicate(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+redacted_fmt!(SecretString);
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Secret secp256k1 scalar, e.g. a decoded share or a Shamir shard.
+pub struct SecretScalar(Scalar);
+
+impl SecretScalar {
+    pub fn new(scalar: Scalar) -> Self {
+        Self(scalar)
+    }
+
+    pub fn expose_secret(&self) -> &Scalar {
+        &self.0
+    }
+
+    pub fn duplicate(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+impl From<Scalar> for SecretScalar {
+    fn from(scalar: Scalar) -> Self {
+        Self(scalar)
+    }
+}
+
+impl PartialEq for SecretScalar {
+    fn eq(&self, other: &Self) -> bool {
+        use k256::elliptic_curve::subtle::ConstantTimeEq;
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for SecretScalar {}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+redacted_fmt!(SecretScalar);
@@ -1,21 +1,36 @@
 use anyhow::{Result, anyhow};
 use k256::Scalar;
 use k256::elliptic_curve::Field;
-use rand_core::OsRng;
+use rand_core::{CryptoRng, OsRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::secret::SecretScalar;
 
 // Simple Shamir Secret Sharing over Secp256k1 Scalar field
 
+/// A Shamir shard: `(index, share)`, where the index is the x-coordinate.
+pub type Shard = (usize, SecretScalar);
+
 /// Split a secret into N shares, with threshold K
-pub fn split_secret(secret: &Scalar, threshold: usize, total: usize) -> Vec<(usize, Scalar)> {
+pub fn split_secret(secret: &SecretScalar, threshold: usize, total: usize) -> Vec<Shard> {
+    split_secret_with_rng(secret, threshold, total, &mut OsRng)
+}
+
+pub fn split_secret_with_rng<R: CryptoRng + RngCore>(
+    secret: &SecretScalar,
+    threshold: usize,
+    total: usize,
+    rng: &mut R,
+) -> Vec<Shard> {
     assert!(threshold <= total);
 
     // 1. Generate coefficients a_1 ... a_{k-1}
     // a_0 is the secret
-    let mut coefficients = Vec::with_capacity(threshold);
-    coefficients.push(*secret); // a_0
+    let mut coefficients = Zeroizing::new(Vec::with_capacity(threshold));
+    coefficients.push(*secret.expose_secret()); // a_0
 
     for _ in 1..threshold {
-        coefficients.push(Scalar::random(&mut OsRng));
+        coefficients.push(Scalar::random(&mut *rng));
     }
 
     // 2. Evaluate polynomial at x = 1..=total
@@ -32,14 +47,14 @@ pub fn split_secret(secret: &Scalar, threshold: usize, total: usize) -> Vec<(usi
             // Let's try values.
             y += *coeff * x_pow_i;
         }
-        shares.push((x, y));
+        shares.push((x, SecretScalar::new(y)));
     }
 
     shares
 }
 
 /// Recover secret from K shares using Lagrange Interpolation
-pub fn recover_secret(shares: &[(usize, Scalar)]) -> Result<Scalar> {
+pub fn recover_secret(shares: &[Shard]) -> Result<SecretScalar> {
     if shares.is_empty() {
         return Err(anyhow!("No shares provided"));
     }
@@ -63,10 +78,32 @@ pub fn recover_secret(shares: &[(usize, Scalar)]) -> Result<Scalar> {
             denominator *= x_m - x_j;
         }
 
-        let lagrange_coeff = numerator * denominator.invert().unwrap();
-        secret += *y_j * lagrange_coeff;
+        let denominator_inv = Option::<Scalar>::from(denominator.invert())
+            .ok_or(anyhow!("Shard index {} appears more than once", x_j_idx))?;
+        secret += *y_j.expose_secret() * numerator * denominator_inv;
     }
 
+    Ok(SecretScalar::new(secret))
+}
+
+/// Recovers from every run of `threshold` consecutive shares and requires them
+/// to agree, so a corrupt shard is caught whenever more than `threshold` are given.
+pub fn recover_secret_checked(shares: &[Shard], threshold: usize) -> Result<SecretScalar> {
+    if threshold == 0 || shares.len() < threshold {
+        return Err(anyhow!(
+            "Not enough shards: got {}, threshold is {}",
+            shares.len(),
+            threshold
+        ));
+    }
+    let secret = recover_secret(&shares[..threshold])?;
+    for window in shares.windows(threshold).skip(1) {
+        if recover_secret(window)? != secret {
+            return Err(anyhow!(
+                "Shards disagree: different subsets recover different keys"
+            ));
+        }
+    }
     Ok(secret)
 }
 
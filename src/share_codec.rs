@@ -0,0 +1,99 @@
+use group::secp256k1::Scalar as GroupScalar;
+use k256::elliptic_curve::PrimeField;
+use k256::{FieldBytes, Scalar};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::secret::SecretScalar;
+
+// Wire format of a share, i.e. the BCS encoding of `group::secp256k1::Scalar`:
+//
+//     0x20 || s (32 bytes, big-endian, s < n)
+//
+// The leading byte is the ULEB128 length prefix BCS writes for the 32-byte
+// payload. Anything else (other lengths, other prefixes, s >= n) is rejected
+// rather than truncated or reduced, so a share can never decode to a different
+// scalar than the one that was encoded.
+
+/// Length in bytes of an encoded share.
+pub const SHARE_LEN: usize = 33;
+/// Length in bytes of the raw big-endian scalar inside a share.
+pub const SCALAR_LEN: usize = 32;
+const LENGTH_PREFIX: u8 = SCALAR_LEN as u8;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ShareCodecError {
+    #[error("invalid share length: expected {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("invalid share length prefix: expected 0x20, got {0:#04x}")]
+    InvalidPrefix(u8),
+    #[error("share is not a canonical scalar (value >= curve order)")]
+    OutOfRange,
+    #[error("group scalar encoding failed: {0}")]
+    Group(String),
+}
+
+/// A secp256k1 scalar share with a fixed, canonical encoding.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Share(SecretScalar);
+
+impl Share {
+    pub fn from_scalar(scalar: Scalar) -> Self {
+        Self(SecretScalar::new(scalar))
+    }
+
+    pub fn scalar(&self) -> &Scalar {
+        self.0.expose_secret()
+    }
+
+    pub fn into_secret(self) -> SecretScalar {
+        self.0
+    }
+
+    /// Encodes as `0x20 || big-endian scalar`.
+    pub fn encode(&self) -> Zeroizing<[u8; SHARE_LEN]> {
+        let mut out = Zeroizing::new([0u8; SHARE_LEN]);
+        out[0] = LENGTH_PREFIX;
+        out[1..].copy_from_slice(&self.scalar().to_bytes());
+        out
+    }
+
+    /// Strict inverse of `encode`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ShareCodecError> {
+        if bytes.len() != SHARE_LEN {
+            return Err(ShareCodecError::InvalidLength {
+                expected: SHARE_LEN,
+                actual: bytes.len(),
+            });
+        }
+        if bytes[0] != LENGTH_PREFIX {
+            return Err(ShareCodecError::InvalidPrefix(bytes[0]));
+        }
+        scalar_from_be_bytes(&bytes[1..]).map(Self::from_scalar)
+    }
+
+    pub fn from_group(scalar: &GroupScalar) -> Result<Self, ShareCodecError> {
+        let bytes = Zeroizing::new(
+            bcs::to_bytes(scalar).map_err(|e| ShareCodecError::Group(e.to_string()))?,
+        );
+        Self::decode(&bytes)
+    }
+
+    pub fn to_group(&self) -> Result<GroupScalar, ShareCodecError> {
+        bcs::from_bytes(&self.encode()[..]).map_err(|e| ShareCodecError::Group(e.to_string()))
+    }
+}
+
+/// Decodes a raw 32-byte big-endian scalar (the shard file format), rejecting s >= n.
+/// The result is a plain `Scalar`; callers holding secrets wrap it in `SecretScalar`.
+pub fn scalar_from_be_bytes(bytes: &[u8]) -> Result<Scalar, ShareCodecError> {
+    if bytes.len() != SCALAR_LEN {
+        return Err(ShareCodecError::InvalidLength {
+            expected: SCALAR_LEN,
+            actual: bytes.len(),
+        });
+    }
+    let mut repr = FieldBytes::default();
+    repr.copy_from_slice(bytes);
+    Option::from(Scalar::from_repr(repr)).ok_or(ShareCodecError::OutOfRange)
+}
@@ -0,0 +1,297 @@
+use crate::audit_log::{AuditEvent, AuditLog};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use thiserror::Error;
+
+pub const DAY_SECS: u64 = 24 * 60 * 60;
+pub const WEEK_SECS: u64 = 7 * DAY_SECS;
+
+/// Declarative limits on what the vault may sign, checked after the vote and
+/// before the TEE signs. Every rule is optional; an empty policy (`{}`) allows
+/// any transfer the quorum approves.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SpendingPolicy {
+    /// Largest single transfer, in MIST.
+    #[serde(default)]
+    pub max_per_transaction: Option<u64>,
+    /// Total MIST over any rolling 24 hours.
+    #[serde(default)]
+    pub daily_limit: Option<u64>,
+    /// Total MIST over any rolling 7 days.
+    #[serde(default)]
+    pub weekly_limit: Option<u64>,
+    /// When non-empty, the only recipients allowed.
+    #[serde(default)]
+    pub allowlist: BTreeSet<String>,
+    /// Recipients that are always refused.
+    #[serde(default)]
+    pub denylist: BTreeSet<String>,
+    /// Voting weight required on top of the quorum for large transfers.
+    #[serde(default)]
+    pub approval_tiers: Vec<ApprovalTier>,
+}
+
+/// Transfers strictly above `above` need `extra_weight` more than the quorum.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ApprovalTier {
+    pub above: u64,
+    pub extra_weight: u64,
+}
+
+/// Why the policy refused a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PolicyViolation {
+    #[error("recipient {0} is on the denylist")]
+    Denylisted(String),
+    #[error("recipient {0} is not on the allowlist")]
+    NotAllowlisted(String),
+    #[error("{amount} MIST exceeds the per-transaction cap of {cap}")]
+    TransactionCap { amount: u64, cap: u64 },
+    #[error(
+        "{amount} MIST on top of {spent} spent in the last 24h exceeds the daily limit of {limit}"
+    )]
+    DailyLimit { amount: u64, spent: u64, limit: u64 },
+    #[error(
+        "{amount} MIST on top of {spent} spent in the last 7 days exceeds the weekly limit of {limit}"
+    )]
+    WeeklyLimit { amount: u64, spent: u64, limit: u64 },
+    #[error("transfers above {above} MIST need {required} approval weight, got {approved}")]
+    ApprovalWeight {
+        above: u64,
+        approved: u64,
+        required: u64,
+    },
+}
+
+impl SpendingPolicy {
+    /// Reads a policy file. A missing file is an error, so deleting it cannot
+    /// lift the limits; callers opt out explicitly with `SpendingPolicy::default()`.
+    pub fn load(path: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                let policy: Self = serde_json::from_str(&content)
+                    .map_err(|e| anyhow!("Invalid spending policy '{}': {}", path, e))?;
+                policy.validate()?;
+                Ok(policy)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(anyhow!(
+                "No spending policy at '{}'; write one ({{}} for no limits) or pass --no-policy",
+                path
+            )),
+            Err(e) => Err(anyhow!("Failed to read spending policy '{}': {}", path, e)),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(recipient) = self
+            .allowlist
+            .iter()
+            .find(|r| list_contains(&self.denylist, r))
+        {
+            return Err(anyhow!(
+                "{} is on both the allowlist and the denylist",
+                recipient
+            ));
+        }
+        if let (Some(daily), Some(weekly)) = (self.daily_limit, self.weekly_limit) {
+            if daily > weekly {
+                return Err(anyhow!(
+                    "Daily limit {} exceeds the weekly limit {}",
+                    daily,
+                    weekly
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The tier that applies to `amount`: the highest `above` it exceeds.
+    pub fn approval_tier(&self, amount: u64) -> Option<&ApprovalTier> {
+        self.approval_tiers
+            .iter()
+            .filter(|tier| amount > tier.above)
+            .max_by_key(|tier| tier.above)
+    }
+
+    /// Approval weight a transfer of `amount` needs, given the group's quorum.
+    pub fn required_weight(&self, amount: u64, quorum_weight: u64) -> u64 {
+        quorum_weight.saturating_add(self.approval_tier(amount).map_or(0, |t| t.extra_weight))
+    }
+
+    /// Every rule the transfer breaks; empty means allowed. `approved_weight`
+    /// is what the vote gathered and `quorum_weight` what it needed.
+    pub fn evaluate(
+        &self,
+        ledger: &SpendingLedger,
+        recipient: &str,
+        amount: u64,
+        approved_weight: u64,
+        quorum_weight: u64,
+        now: u64,
+    ) -> Vec<PolicyViolation> {
+        let mut violations = Vec::new();
+        let recipient = normalize_address(recipient);
+        if list_contains(&self.denylist, &recipient) {
+            violations.push(PolicyViolation::Denylisted(recipient.clone()));
+        }
+        if !self.allowlist.is_empty() && !list_contains(&self.allowlist, &recipient) {
+            violations.push(PolicyViolation::NotAllowlisted(recipient));
+        }
+        if let Some(cap) = self.max_per_transaction {
+            if amount > cap {
+                violations.push(PolicyViolation::TransactionCap { amount, cap });
+            }
+        }
+        if let Some(limit) = self.daily_limit {
+            let spent = ledger.spent_since(now.saturating_sub(DAY_SECS));
+            if spent.saturating_add(amount) > limit {
+                violations.push(PolicyViolation::DailyLimit {
+                    amount,
+                    spent,
+                    limit,
+                });
+            }
+        }
+        if let Some(limit) = self.weekly_limit {
+            let spent = ledger.spent_since(now.saturating_sub(WEEK_SECS));
+            if spent.saturating_add(amount) > limit {
+                violations.push(PolicyViolation::WeeklyLimit {
+                    amount,
+                    spent,
+                    limit,
+                });
+            }
+        }
+        if let Some(tier) = self.approval_tier(amount) {
+            let required = self.required_weight(amount, quorum_weight);
+            if approved_weight < required {
+                violations.push(PolicyViolation::ApprovalWeight {
+                    above: tier.above,
+                    approved: approved_weight,
+                    required,
+                });
+            }
+        }
+        violations
+    }
+
+    /// `evaluate` as an error listing every denial reason.
+    pub fn check(
+        &self,
+        ledger: &SpendingLedger,
+        recipient: &str,
+        amount: u64,
+        approved_weight: u64,
+        quorum_weight: u64,
+        now: u64,
+    ) -> Result<()> {
+        let violations = self.evaluate(
+            ledger,
+            recipient,
+            amount,
+            approved_weight,
+            quorum_weight,
+            now,
+        );
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        Err(anyhow!(
+            "Spending policy denied the transfer: {}",
+            reasons.join("; ")
+        ))
+    }
+}
+
+/// One signed transfer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Spend {
+    pub proposal_id: u64,
+    pub recipient: String,
+    pub amount: u64,
+    pub executed_at: u64,
+}
+
+/// Persisted history of signed transfers, used for the rolling limits.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SpendingLedger {
+    pub spends: Vec<Spend>,
+}
+
+impl SpendingLedger {
+    /// Starts empty when the file does not exist yet; `absorb_audit_log` then
+    /// restores the transfers the audit log records.
+    pub fn load(path: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!("Failed to read spending ledger '{}': {}", path, e)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Drops the spend recorded for `proposal_id`, e.g. when signing failed.
+    pub fn unrecord(&mut self, proposal_id: u64) {
+        self.spends.retain(|spend| spend.proposal_id != proposal_id);
+    }
+
+    pub fn record(&mut self, proposal_id: u64, recipient: &str, amount: u64, executed_at: u64) {
+        self.spends.push(Spend {
+            proposal_id,
+            recipient: normalize_address(recipient),
+            amount,
+            executed_at,
+        });
+    }
+
+    /// Adds every transfer the audit log records as executed that the ledger
+    /// lacks, so a deleted or rolled-back file does not reset the limits.
+    pub fn absorb_audit_log(&mut self, log: &AuditLog) {
+        let mut created = HashMap::new();
+        for entry in &log.entries {
+            match &entry.event {
+                AuditEvent::ProposalCreated {
+                    id,
+                    recipient,
+                    amount,
+                    ..
+                } => {
+                    created.insert(*id, (recipient, *amount));
+                }
+                AuditEvent::ProposalExecuted { id, .. } => {
+                    if let Some((recipient, amount)) = created.get(id) {
+                        if !self.spends.iter().any(|spend| spend.proposal_id == *id) {
+                            self.record(*id, recipient, *amount, entry.timestamp);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// MIST signed strictly after `since`.
+    pub fn spent_since(&self, since: u64) -> u64 {
+        self.spends
+            .iter()
+            .filter(|spend| spend.executed_at > since)
+            .fold(0u64, |total, spend| total.saturating_add(spend.amount))
+    }
+}
+
+fn normalize_address(address: &str) -> String {
+    address.trim().to_ascii_lowercase()
+}
+
+fn list_contains(list: &BTreeSet<String>, address: &str) -> bool {
+    let address = normalize_address(address);
+    list.iter().any(|entry| normalize_address(entry) == address)
+}
@@ -1,19 +1,121 @@
 use anyhow::{Result, anyhow};
+use fastcrypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use fastcrypto::encoding::{Base64, Encoding};
 use fastcrypto::hash::{Blake2b256, HashFunction};
-use k256::ecdsa::VerifyingKey;
+use fastcrypto::secp256r1::{Secp256r1PublicKey, Secp256r1Signature};
+use fastcrypto::traits::{ToFromBytes, VerifyingKey as _};
+use k256::ecdsa::signature::{Signer, Verifier};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
 use k256::elliptic_curve::group::GroupEncoding;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::multisig::MultiSig;
+use crate::secret::{SecretBytes, SecretScalar};
+use crate::share_codec::scalar_from_be_bytes;
+
+/// A Sui signature scheme a member key can use. Serialized signatures and
+/// addresses start from the scheme's flag byte.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    Ed25519,
+    #[default]
+    Secp256k1,
+    /// Passkeys (WebAuthn) and other P-256 hardware keys.
+    Secp256r1,
+}
+
+impl SignatureScheme {
+    pub const ALL: [SignatureScheme; 3] = [
+        SignatureScheme::Secp256k1,
+        SignatureScheme::Secp256r1,
+        SignatureScheme::Ed25519,
+    ];
+
+    pub fn flag(self) -> u8 {
+        match self {
+            SignatureScheme::Ed25519 => ED25519_FLAG,
+            SignatureScheme::Secp256k1 => SECP256K1_FLAG,
+            SignatureScheme::Secp256r1 => SECP256R1_FLAG,
+        }
+    }
+
+    pub fn from_flag(flag: u8) -> Result<Self> {
+        SignatureScheme::ALL
+            .into_iter()
+            .find(|s| s.flag() == flag)
+            .ok_or(anyhow!("Unsupported signature scheme flag {:#04x}", flag))
+    }
+
+    /// Length of the public key in Sui's encoding (compressed for ECDSA).
+    pub fn pubkey_len(self) -> usize {
+        match self {
+            SignatureScheme::Ed25519 => 32,
+            SignatureScheme::Secp256k1 | SignatureScheme::Secp256r1 => 33,
+        }
+    }
+}
+
+impl fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SignatureScheme::Ed25519 => "ed25519",
+            SignatureScheme::Secp256k1 => "secp256k1",
+            SignatureScheme::Secp256r1 => "secp256r1",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        SignatureScheme::ALL
+            .into_iter()
+            .find(|scheme| scheme.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(anyhow!(
+                "Unknown signature scheme '{}' (expected secp256k1, secp256r1 or ed25519)",
+                s
+            ))
+    }
+}
 
 /// Converts our MPC Secp256k1 Public Key to a real Sui Address
 pub fn pubkey_to_sui_address(verifying_key: &VerifyingKey) -> String {
-    let pubkey_bytes = verifying_key.to_encoded_point(true).as_bytes().to_vec();
-
-    let mut data = vec![0x01]; // Secp256k1 Flag
-    data.extend_from_slice(&pubkey_bytes);
+    public_key_to_sui_address(
+        SignatureScheme::Secp256k1,
+        verifying_key.to_encoded_point(true).as_bytes(),
+    )
+}
 
-    let hash = Blake2b256::digest(&data);
+/// Sui address of a public key: Blake2b256(flag || pubkey).
+pub fn public_key_to_sui_address(scheme: SignatureScheme, pubkey: &[u8]) -> String {
+    let mut data = vec![scheme.flag()];
+    data.extend_from_slice(pubkey);
+    format!("0x{}", hex::encode(Blake2b256::digest(&data).digest))
+}
 
-    // Sui Address is first 32 bytes of Blake2b256(flag || pubkey)
-    format!("0x{}", hex::encode(&hash.digest[0..32]))
+/// Checks `pubkey` is a valid key of `scheme` and returns it in Sui's encoding.
+pub fn normalize_public_key(scheme: SignatureScheme, pubkey: &[u8]) -> Result<Vec<u8>> {
+    match scheme {
+        SignatureScheme::Secp256k1 => Ok(VerifyingKey::from_sec1_bytes(pubkey)
+            .map_err(|e| anyhow!("Invalid secp256k1 public key: {}", e))?
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()),
+        SignatureScheme::Secp256r1 => Ok(Secp256r1PublicKey::from_bytes(pubkey)
+            .map_err(|_| anyhow!("Invalid secp256r1 public key"))?
+            .as_bytes()
+            .to_vec()),
+        SignatureScheme::Ed25519 => Ok(Ed25519PublicKey::from_bytes(pubkey)
+            .map_err(|_| anyhow!("Invalid ed25519 public key"))?
+            .as_bytes()
+            .to_vec()),
+    }
 }
 
 /// Builds a mock Sui Transaction Digest
@@ -37,3 +139,249 @@ pub fn build_and_hash_sui_tx(sender: &str, recipient: &str, amount: u64) -> Resu
 
     Ok(hash.digest.to_vec())
 }
+
+/// Intent of `signPersonalMessage`: scope PersonalMessage(3), version V0, app Sui.
+pub const PERSONAL_MESSAGE_INTENT: [u8; 3] = [3, 0, 0];
+
+/// What a Sui wallet signs for `signPersonalMessage`:
+/// Blake2b256(intent || BCS(message)), BCS being a ULEB128 length prefix.
+pub fn personal_message_digest(message: &[u8]) -> Result<[u8; 32]> {
+    let mut data = PERSONAL_MESSAGE_INTENT.to_vec();
+    data.extend(bcs::to_bytes(message)?);
+    Ok(Blake2b256::digest(&data).digest)
+}
+
+/// Signs `message` as a Sui personal message and returns the serialized
+/// signature, base64(flag || r || s || compressed pubkey), as a wallet would.
+pub fn sign_personal_message(signing_key: &SigningKey, message: &[u8]) -> Result<String> {
+    // Sui's secp256k1 scheme is ECDSA over SHA-256 of the digest, low-s.
+    let signature: Signature = signing_key.sign(&personal_message_digest(message)?);
+    Ok(serialize_signature(
+        SignatureScheme::Secp256k1,
+        &signature.to_bytes(),
+        VerifyingKey::from(signing_key)
+            .to_encoded_point(true)
+            .as_bytes(),
+    ))
+}
+
+/// Serializes a raw signature the way Sui wallets do: base64(flag || sig || pubkey).
+pub fn serialize_signature(scheme: SignatureScheme, signature: &[u8], pubkey: &[u8]) -> String {
+    let mut serialized = vec![scheme.flag()];
+    serialized.extend_from_slice(signature);
+    serialized.extend_from_slice(pubkey);
+    Base64::encode(serialized)
+}
+
+/// Verifies a serialized Sui signature over a personal message and returns the
+/// address of the key that signed it. The flag byte selects the scheme; a
+/// MultiSig returns the MultiSig address.
+pub fn verify_personal_message(message: &[u8], serialized: &str) -> Result<String> {
+    let bytes = Base64::decode(serialized).map_err(|_| anyhow!("Signature is not base64"))?;
+    let (&flag, rest) = bytes.split_first().ok_or(anyhow!("Empty signature"))?;
+    if flag == MULTISIG_FLAG {
+        return MultiSig::parse(serialized)?.verify(message);
+    }
+    let scheme = SignatureScheme::from_flag(flag)?;
+    if rest.len() != 64 + scheme.pubkey_len() {
+        return Err(anyhow!("Invalid {} signature length", scheme));
+    }
+    let (signature, pubkey) = rest.split_at(64);
+    let digest = personal_message_digest(message)?;
+    let verified = match scheme {
+        // Sui's ECDSA schemes sign SHA-256 of the digest; Ed25519 signs it as is.
+        SignatureScheme::Secp256k1 => {
+            let verifying_key = VerifyingKey::from_sec1_bytes(pubkey)
+                .map_err(|e| anyhow!("Invalid signer public key: {}", e))?;
+            let signature = Signature::from_slice(signature)
+                .map_err(|e| anyhow!("Invalid signature format: {}", e))?;
+            verifying_key.verify(&digest, &signature).is_ok()
+        }
+        SignatureScheme::Secp256r1 => {
+            let public_key = Secp256r1PublicKey::from_bytes(pubkey)
+                .map_err(|_| anyhow!("Invalid signer public key"))?;
+            let signature = Secp256r1Signature::from_bytes(signature)
+                .map_err(|_| anyhow!("Invalid signature format"))?;
+            public_key.verify(&digest, &signature).is_ok()
+        }
+        SignatureScheme::Ed25519 => {
+            let public_key = Ed25519PublicKey::from_bytes(pubkey)
+                .map_err(|_| anyhow!("Invalid signer public key"))?;
+            let signature = Ed25519Signature::from_bytes(signature)
+                .map_err(|_| anyhow!("Invalid signature format"))?;
+            public_key.verify(&digest, &signature).is_ok()
+        }
+    };
+    if !verified {
+        return Err(anyhow!("Signature does not verify"));
+    }
+    Ok(public_key_to_sui_address(scheme, pubkey))
+}
+
+/// Human-readable part of Sui's bech32 private key encoding.
+pub const SUI_PRIV_KEY_PREFIX: &str = "suiprivkey";
+/// Sui signature scheme flags.
+pub const ED25519_FLAG: u8 = 0x00;
+pub const SECP256K1_FLAG: u8 = 0x01;
+pub const SECP256R1_FLAG: u8 = 0x02;
+pub const MULTISIG_FLAG: u8 = 0x03;
+
+/// Parses an existing secp256k1 private key, given either as 32-byte hex
+/// (optionally `0x`-prefixed) or as a Sui `suiprivkey1...` string.
+pub fn parse_secp256k1_private_key(input: &str) -> Result<SecretScalar> {
+    let input = input.trim();
+    let raw = if input.to_lowercase().starts_with(SUI_PRIV_KEY_PREFIX) {
+        decode_sui_private_key(input)?
+    } else {
+        let hex_str = input.strip_prefix("0x").unwrap_or(input);
+        SecretBytes::new(hex::decode(hex_str).map_err(|_| anyhow!("Private key is not valid hex"))?)
+    };
+    let scalar = scalar_from_be_bytes(raw.expose_secret())
+        .map_err(|e| anyhow!("Invalid secp256k1 private key: {}", e))?;
+    if bool::from(scalar.is_zero()) {
+        return Err(anyhow!("Invalid secp256k1 private key: zero"));
+    }
+    Ok(SecretScalar::new(scalar))
+}
+
+/// Decodes `suiprivkey1...` into the raw 32-byte secp256k1 key.
+pub fn decode_sui_private_key(encoded: &str) -> Result<SecretBytes> {
+    let (hrp, payload) = bech32_decode(encoded)?;
+    if hrp != SUI_PRIV_KEY_PREFIX {
+        return Err(anyhow!(
+            "Expected '{}' prefix, got '{}'",
+            SUI_PRIV_KEY_PREFIX,
+            hrp
+        ));
+    }
+    match payload.expose_secret() {
+        [SECP256K1_FLAG, key @ ..] if key.len() == 32 => Ok(SecretBytes::new(key.to_vec())),
+        [SECP256K1_FLAG, ..] => Err(anyhow!("Invalid secp256k1 key length in suiprivkey")),
+        [flag, ..] => Err(anyhow!(
+            "Unsupported key scheme flag {:#04x}; only secp256k1 keys can be imported",
+            flag
+        )),
+        [] => Err(anyhow!("Empty suiprivkey payload")),
+    }
+}
+
+/// Encodes a raw 32-byte secp256k1 key as `suiprivkey1...`.
+pub fn encode_sui_private_key(key: &SecretBytes) -> Result<String> {
+    if key.expose_secret().len() != 32 {
+        return Err(anyhow!("secp256k1 private key must be 32 bytes"));
+    }
+    let mut payload = Zeroizing::new(vec![SECP256K1_FLAG]);
+    payload.extend_from_slice(key.expose_secret());
+    Ok(bech32_encode(SUI_PRIV_KEY_PREFIX, &payload))
+}
+
+// Minimal BIP-173 bech32 (the variant Sui uses for private keys).
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CHECKSUM_LEN: usize = 6;
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk = 1u32;
+    for v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ u32::from(*v);
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+/// Regroups bits; decoding (`pad == false`) rejects leftover non-zero padding.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>> {
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::new();
+    for value in data {
+        acc = (acc << from) | u32::from(*value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    let bad_padding = if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+        false
+    } else {
+        bits >= from || ((acc << (to - bits)) & max) != 0
+    };
+    acc.zeroize();
+    if bad_padding {
+        out.zeroize();
+        return Err(anyhow!("Invalid bech32 padding"));
+    }
+    Ok(out)
+}
+
+pub(crate) fn bech32_encode(hrp: &str, payload: &[u8]) -> String {
+    let data = Zeroizing::new(convert_bits(payload, 8, 5, true).unwrap_or_default());
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    values.extend_from_slice(&[0; BECH32_CHECKSUM_LEN]);
+    let polymod = bech32_polymod(&values) ^ 1;
+    values.zeroize();
+
+    let mut out = format!("{}1", hrp);
+    for v in data.iter() {
+        out.push(BECH32_CHARSET[*v as usize] as char);
+    }
+    for i in 0..BECH32_CHECKSUM_LEN {
+        let v = (polymod >> (5 * (5 - i))) & 31;
+        out.push(BECH32_CHARSET[v as usize] as char);
+    }
+    out
+}
+
+pub(crate) fn bech32_decode(encoded: &str) -> Result<(String, SecretBytes)> {
+    if encoded.chars().any(|c| c.is_ascii_lowercase())
+        && encoded.chars().any(|c| c.is_ascii_uppercase())
+    {
+        return Err(anyhow!("Mixed-case bech32 string"));
+    }
+    let encoded = Zeroizing::new(encoded.to_lowercase());
+    let sep = encoded
+        .rfind('1')
+        .ok_or(anyhow!("Missing bech32 separator"))?;
+    let (hrp, data_part) = (&encoded[..sep], &encoded[sep + 1..]);
+    if hrp.is_empty() || data_part.len() < BECH32_CHECKSUM_LEN {
+        return Err(anyhow!("Bech32 string too short"));
+    }
+
+    let mut values = Zeroizing::new(Vec::with_capacity(data_part.len()));
+    for c in data_part.bytes() {
+        let v = BECH32_CHARSET
+            .iter()
+            .position(|x| *x == c)
+            .ok_or(anyhow!("Invalid bech32 character"))?;
+        values.push(v as u8);
+    }
+
+    let mut check = Zeroizing::new(bech32_hrp_expand(hrp));
+    check.extend_from_slice(&values);
+    if bech32_polymod(&check) != 1 {
+        return Err(anyhow!("Invalid bech32 checksum"));
+    }
+
+    let data = &values[..values.len() - BECH32_CHECKSUM_LEN];
+    let payload = convert_bits(data, 5, 8, false)?;
+    Ok((hrp.to_string(), SecretBytes::new(payload)))
+}
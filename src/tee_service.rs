@@ -5,12 +5,14 @@ use dwallet_mpc_centralized_party::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::secret::SecretBytes;
+
 /// Represents the TEE's local storage of the key material
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize)]
 pub struct TeeKeyStore {
     pub share_encryption_key: Vec<u8>,
-    pub share_decryption_key: Vec<u8>,
-    pub dwallet_secret_share: Option<Vec<u8>>,
+    pub share_decryption_key: SecretBytes,
+    pub dwallet_secret_share: Option<SecretBytes>,
 }
 
 pub struct TeeMpcService {
@@ -22,9 +24,11 @@ impl TeeMpcService {
         Self { curve }
     }
 
-    pub fn generate_encryption_keypair(&self, seed: [u8; 32]) -> Result<(Vec<u8>, Vec<u8>)> {
-        generate_cg_keypair_from_seed(self.curve, seed)
-            .map_err(|e| anyhow!("Failed to generate encryption keypair: {}", e))
+    /// Returns `(encryption_key, decryption_key)`.
+    pub fn generate_encryption_keypair(&self, seed: [u8; 32]) -> Result<(Vec<u8>, SecretBytes)> {
+        let (encryption_key, decryption_key) = generate_cg_keypair_from_seed(self.curve, seed)
+            .map_err(|e| anyhow!("Failed to generate encryption keypair: {}", e))?;
+        Ok((encryption_key, SecretBytes::new(decryption_key)))
     }
 
     pub fn initiate_dkg(&self, protocol_pp: Vec<u8>, session_id: Vec<u8>) -> Result<Vec<u8>> {
@@ -36,13 +40,16 @@ impl TeeMpcService {
 
     pub fn re_encrypt_share(
         &self,
-        secret_share: Vec<u8>,
+        secret_share: &SecretBytes,
         new_encryption_key_public: Vec<u8>,
         protocol_pp: Vec<u8>,
     ) -> Result<Vec<u8>> {
+        // The MPC library takes the share as a plain `Vec<u8>` by value. That copy
+        // is not zeroized: it lives only for this call and is freed, not wiped,
+        // when the library drops it.
         encrypt_secret_key_share_and_prove_v2(
             self.curve,
-            secret_share,
+            secret_share.expose_secret().to_vec(),
             new_encryption_key_public,
             protocol_pp,
         )
@@ -53,16 +60,17 @@ impl TeeMpcService {
         &self,
         protocol_pp: Vec<u8>,
         decentralized_party_dkg_output: Vec<u8>,
-        user_secret_share: Vec<u8>,
+        user_secret_share: &SecretBytes,
         presign: Vec<u8>,
         message: Vec<u8>,
         signature_algo: u32,
         hash_scheme: u32,
     ) -> Result<Vec<u8>> {
+        // As in `re_encrypt_share`, the library gets an unzeroized copy of the share.
         advance_centralized_sign_party(
             protocol_pp,
             decentralized_party_dkg_output,
-            user_secret_share,
+            user_secret_share.expose_secret().to_vec(),
             presign,
             message,
             self.curve,
@@ -1,17 +1,59 @@
 #[cfg(test)]
 mod tests {
-    use crate::pss::perform_pss_refresh;
-    use crate::sharding::{recover_secret, split_secret};
-    use crate::sui_utils::{build_and_hash_sui_tx, pubkey_to_sui_address};
+    use crate::audit_log::{AuditEntry, AuditEvent, AuditLog, GENESIS_HASH};
+    use crate::break_glass::{ExportRequest, export_vault_key, open_export, required_approvals};
+    use crate::dao::{
+        DaoGroup, Delegation, DelegationScope, GovernanceAction, GovernanceMode, Member, MemberKey,
+        Permission, ProposalKind, Role, TalliedSigner, Timelock,
+    };
+    use crate::group_schema::{
+        GROUP_SCHEMA_VERSION, UNVERSIONED, migrate, parse_group, schema_version,
+    };
+    use crate::keystore::{MemberKeystore, keystore_path};
+    use crate::move_model::{
+        ACTION_ADD_TEE, ACTION_NONE, ACTION_REMOVE_TEE, Call, Event, MoveAbort, SovereignDao,
+        is_trusted_tee, proposal_status,
+    };
+    use crate::multisig::{MultiSig, MultiSigPublicKey};
+    use crate::proposal::{DEFAULT_CHAIN_ID, NonceLedger, Proposal, TransferProposal};
+    use crate::proposal_store::{ExecutionRecord, ProposalStatus, ProposalStore};
+    use crate::pss::{
+        generate_additive_shares, generate_initial_shares_with_rng, mock_sign_and_verify,
+        perform_additive_refresh, perform_pss_refresh, perform_sharded_refresh, split_existing_key,
+    };
+    use crate::refresh_scheduler::{RefreshPolicy, RefreshReason, RefreshScheduler};
+    use crate::scalar_utils::{bytes_to_scalar, legacy_bytes_to_scalar, scalar_to_bytes};
+    use crate::secret::{SecretBytes, SecretScalar, SecretString};
+    use crate::sharding::{
+        recover_secret, recover_secret_checked, split_secret, split_secret_with_rng,
+    };
+    use crate::share_codec::{Share, ShareCodecError, scalar_from_be_bytes};
+    use crate::spending_policy::{
+        DAY_SECS, PolicyViolation, SpendingLedger, SpendingPolicy, WEEK_SECS,
+    };
+    use crate::sui_utils::{
+        SignatureScheme, bech32_decode, bech32_encode, build_and_hash_sui_tx,
+        encode_sui_private_key, parse_secp256k1_private_key, personal_message_digest,
+        pubkey_to_sui_address, public_key_to_sui_address, verify_personal_message,
+    };
+    use crate::two_party::{ENCRYPTION_SEED_LEN, two_party_sign};
     use dwallet_mpc_types::dwallet_mpc::DWalletCurve;
+    use fastcrypto::encoding::{Base64, Encoding};
+    use fastcrypto::hash::{Blake2b256, HashFunction};
 
     use group::secp256k1::scalar::PublicParameters;
     use group::{OsCsRng, Samplable};
     use k256::Scalar;
+    use k256::ecdsa::signature::Verifier;
     use k256::ecdsa::{SigningKey, VerifyingKey};
     use k256::elliptic_curve::Field;
     use k256::elliptic_curve::PrimeField;
-    use rand_core::OsRng;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{OsRng, SeedableRng};
+    use std::collections::{BTreeSet, HashMap};
+
+    const VECTOR_RECIPIENT: &str =
+        "0x1111111111111111111111111111111111111111111111111111111111111111";
 
     // Type alias for group::Scalar to avoid confusion with k256::Scalar
     use group::secp256k1::Scalar as GroupScalar;
@@ -29,8 +71,8 @@ mod tests {
         let result = perform_pss_refresh(DWalletCurve::Secp256k1 as u32, &s1_bytes, &s2_bytes)
             .expect("PSS refresh failed");
 
-        let s1_new: GroupScalar = bcs::from_bytes(&result.new_dao_share).unwrap();
-        let s2_new: GroupScalar = bcs::from_bytes(&result.new_tee_share).unwrap();
+        let s1_new: GroupScalar = bcs::from_bytes(result.new_dao_share.expose_secret()).unwrap();
+        let s2_new: GroupScalar = bcs::from_bytes(result.new_tee_share.expose_secret()).unwrap();
 
         let sum_old = s1 + s2;
         let sum_new = s1_new + s2_new;
@@ -43,10 +85,168 @@ mod tests {
         assert_ne!(s2, s2_new, "Share 2 must change");
     }
 
+    #[test]
+    fn test_additive_refresh_three_parties() {
+        let curve = DWalletCurve::Secp256k1 as u32;
+        let shares = generate_additive_shares(&["dao", "tee", "compliance"]).unwrap();
+        let refreshed = perform_additive_refresh(curve, &shares).expect("n-party refresh failed");
+
+        let sum = |shares: &[crate::pss::PartyShare]| {
+            shares
+                .iter()
+                .map(|s| bcs::from_bytes::<GroupScalar>(s.share.expose_secret()).unwrap())
+                .reduce(|acc, s| acc + &s)
+                .unwrap()
+        };
+        assert_eq!(sum(&shares), sum(&refreshed), "Deltas must sum to zero");
+        for (old, new) in shares.iter().zip(&refreshed) {
+            assert_eq!(old.party, new.party);
+            assert_ne!(
+                old.share.expose_secret(),
+                new.share.expose_secret(),
+                "Every party's share must change"
+            );
+        }
+
+        assert!(generate_additive_shares(&["dao"]).is_err());
+        assert!(generate_additive_shares(&["dao", "dao"]).is_err());
+    }
+
+    #[test]
+    fn test_sharded_refresh_preserves_vault_key() {
+        let pp = PublicParameters::default();
+        let s_tee = bcs::to_bytes(&GroupScalar::sample(&pp, &mut OsCsRng).unwrap()).unwrap();
+        let s_dao = SecretScalar::new(Scalar::random(&mut OsRng));
+        let shards = split_secret(&s_dao, 2, 3);
+
+        let result = perform_sharded_refresh(DWalletCurve::Secp256k1 as u32, &s_tee, &shards, 2, 3)
+            .expect("Sharded refresh failed");
+        assert_eq!(result.new_dao_shards.len(), 3);
+
+        let key_old = s_dao.expose_secret() + bytes_to_scalar(&s_tee).unwrap().expose_secret();
+        let key_new = recover_secret(&result.new_dao_shards[1..])
+            .unwrap()
+            .expose_secret()
+            + bytes_to_scalar(result.new_tee_share.expose_secret())
+                .unwrap()
+                .expose_secret();
+        assert_eq!(key_old, key_new, "Vault key must survive a sharded refresh");
+        assert_ne!(
+            s_tee,
+            result.new_tee_share.expose_secret(),
+            "TEE share must change"
+        );
+    }
+
+    #[test]
+    fn test_mock_sign_uses_summed_key() {
+        let (s_dao, s_tee) = generate_initial_shares_with_rng(&mut OsRng).unwrap();
+        let (s_dao, s_tee) = (s_dao.expose_secret(), s_tee.expose_secret());
+        let (_, pubkey_hex) = mock_sign_and_verify(s_dao, s_tee, b"init").unwrap();
+
+        let key = bytes_to_scalar(s_dao).unwrap().expose_secret()
+            + bytes_to_scalar(s_tee).unwrap().expose_secret();
+        let expected = VerifyingKey::from(&SigningKey::from_bytes(&key.to_bytes()).unwrap());
+        assert_eq!(
+            pubkey_hex,
+            hex::encode(expected.to_encoded_point(true).as_bytes())
+        );
+    }
+
+    // --- Share Codec Tests ---
+    #[test]
+    fn test_share_codec_round_trips_both_libraries() {
+        let pp = PublicParameters::default();
+        for _ in 0..16 {
+            // group -> wire -> k256 -> wire -> group
+            let g = GroupScalar::sample(&pp, &mut OsCsRng).unwrap();
+            let wire = bcs::to_bytes(&g).unwrap();
+            let share = Share::decode(&wire).unwrap();
+            assert_eq!(share.encode().to_vec(), wire);
+            assert_eq!(share.to_group().unwrap(), g);
+            assert_eq!(Share::from_group(&g).unwrap(), share);
+
+            // k256 -> wire -> group -> wire -> k256
+            let k = Scalar::random(&mut OsRng);
+            let g = Share::from_scalar(k).to_group().unwrap();
+            assert_eq!(*Share::from_group(&g).unwrap().scalar(), k);
+        }
+    }
+
+    #[test]
+    fn test_share_codec_is_homomorphic() {
+        // Arithmetic must agree across libraries; catches any endianness mix-up.
+        let pp = PublicParameters::default();
+        let a = GroupScalar::sample(&pp, &mut OsCsRng).unwrap();
+        let b = GroupScalar::sample(&pp, &mut OsCsRng).unwrap();
+
+        let ka = *Share::from_group(&a).unwrap().scalar();
+        let kb = *Share::from_group(&b).unwrap().scalar();
+        assert_eq!(*Share::from_group(&(a + b)).unwrap().scalar(), ka + kb);
+
+        let one = Share::from_scalar(Scalar::ONE).encode();
+        assert_eq!(one[0], 0x20);
+        assert_eq!(one[32], 0x01);
+        assert!(one[1..32].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_share_codec_rejects_non_canonical() {
+        let valid = Share::from_scalar(Scalar::random(&mut OsRng)).encode();
+
+        assert!(matches!(
+            Share::decode(&valid[..32]),
+            Err(ShareCodecError::InvalidLength { actual: 32, .. })
+        ));
+        let mut long = valid.to_vec();
+        long.push(0);
+        assert!(matches!(
+            Share::decode(&long),
+            Err(ShareCodecError::InvalidLength { actual: 34, .. })
+        ));
+
+        let mut bad_prefix = valid;
+        bad_prefix[0] = 0x21;
+        assert_eq!(
+            Share::decode(&bad_prefix[..]),
+            Err(ShareCodecError::InvalidPrefix(0x21))
+        );
+
+        // n (the curve order) and 2^256 - 1 are not reduced, they are refused.
+        let order = hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141")
+            .unwrap();
+        let mut at_order = vec![0x20];
+        at_order.extend_from_slice(&order);
+        assert_eq!(Share::decode(&at_order), Err(ShareCodecError::OutOfRange));
+        assert_eq!(
+            scalar_from_be_bytes(&[0xff; 32]),
+            Err(ShareCodecError::OutOfRange)
+        );
+    }
+
+    // --- Secret Type Tests ---
+    #[test]
+    fn test_secret_types_redact_and_keep_wire_format() {
+        let bytes = SecretBytes::new(vec![0xde, 0xad]);
+        let text = SecretString::new("deadbeef".to_string());
+        let scalar = SecretScalar::new(Scalar::ONE);
+        for printed in [
+            format!("{:?} {}", bytes, bytes),
+            format!("{:?} {}", text, text),
+            format!("{:?} {}", scalar, scalar),
+        ] {
+            assert!(printed.contains("[REDACTED]"));
+            assert!(!printed.contains("de") && !printed.contains("222"));
+        }
+
+        // Stored files keep the format of the plain types they replace.
+        assert_eq!(serde_json::to_string(&bytes).unwrap(), "[222,173]");
+    }
+
     // --- Sharding Tests ---
     #[test]
     fn test_shamir_secret_sharing() {
-        let secret = Scalar::random(&mut OsRng);
+        let secret = SecretScalar::new(Scalar::random(&mut OsRng));
 
         // Split into 5 shares, threshold 3
         let shares = split_secret(&secret, 3, 5);
@@ -76,6 +276,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_shard_recovery_rejects_bad_input() {
+        let secret = SecretScalar::new(Scalar::random(&mut OsRng));
+        let shares = split_secret(&secret, 2, 3);
+        let copy = |i: usize| (shares[i].0, shares[i].1.duplicate());
+
+        // The same shard twice has no Lagrange basis; it must not panic.
+        assert!(recover_secret(&[copy(0), copy(0)]).is_err());
+
+        assert_eq!(recover_secret_checked(&shares, 2).unwrap(), secret);
+        assert!(recover_secret_checked(&shares[..1], 2).is_err());
+        // A corrupt shard makes the subsets disagree instead of recovering a
+        // wrong key that "matches" itself.
+        let corrupt = [copy(0), (2, SecretScalar::new(Scalar::ONE)), copy(2)];
+        assert!(recover_secret_checked(&corrupt, 2).is_err());
+    }
+
+    // --- Refresh Scheduler Tests ---
+    #[test]
+    fn test_refresh_scheduler_triggers() {
+        let policy = RefreshPolicy {
+            max_signatures: Some(2),
+            max_interval_secs: Some(100),
+            on_membership_change: true,
+        };
+        let mut scheduler = RefreshScheduler::new(policy, 1_000, "members-v1");
+        assert_eq!(scheduler.due(1_050, "members-v1"), None);
+
+        scheduler.record_signature();
+        scheduler.record_signature();
+        assert_eq!(
+            scheduler.due(1_050, "members-v1"),
+            Some(RefreshReason::SignatureLimit)
+        );
+
+        scheduler.mark_refreshed(1_050, "members-v1");
+        assert_eq!(scheduler.state.epoch, 1);
+        assert_eq!(scheduler.next_due_at(), Some(1_150));
+        assert_eq!(
+            scheduler.due(1_150, "members-v1"),
+            Some(RefreshReason::IntervalElapsed)
+        );
+        assert_eq!(
+            scheduler.due(1_060, "members-v2"),
+            Some(RefreshReason::MembershipChanged)
+        );
+    }
+
+    #[test]
+    fn test_refresh_scheduler_runs_pss() {
+        let pp = PublicParameters::default();
+        let s1 = bcs::to_bytes(&GroupScalar::sample(&pp, &mut OsCsRng).unwrap()).unwrap();
+        let s2 = bcs::to_bytes(&GroupScalar::sample(&pp, &mut OsCsRng).unwrap()).unwrap();
+        let policy = RefreshPolicy {
+            max_signatures: Some(1),
+            ..Default::default()
+        };
+        let mut scheduler = RefreshScheduler::new(policy, 0, "m");
+        let curve = DWalletCurve::Secp256k1 as u32;
+
+        assert!(
+            scheduler
+                .refresh_additive_if_due(curve, &s1, &s2, 0, "m")
+                .unwrap()
+                .is_none()
+        );
+        scheduler.record_signature();
+        let (reason, _) = scheduler
+            .refresh_additive_if_due(curve, &s1, &s2, 0, "m")
+            .unwrap()
+            .expect("Refresh should be due");
+        assert_eq!(reason, RefreshReason::SignatureLimit);
+        // The epoch moves only when the caller has persisted the new shares.
+        assert_eq!(scheduler.state.epoch, 0);
+        scheduler.mark_refreshed(0, "m");
+        assert_eq!(scheduler.state.signatures_since_refresh, 0);
+    }
+
+    // --- Two-Party Signing Tests ---
+    #[test]
+    fn test_two_party_signature_verifies_under_joint_key() {
+        let s_dao = Scalar::random(&mut OsRng);
+        let s_tee = Scalar::random(&mut OsRng);
+        let message = b"transfer 100 MIST";
+
+        let (signature, joint_key) = two_party_sign(
+            SecretScalar::new(s_dao),
+            SecretScalar::new(s_tee),
+            &SecretBytes::new(vec![7; ENCRYPTION_SEED_LEN]),
+            message,
+            &mut OsRng,
+        )
+        .expect("2PC signing failed");
+
+        // The joint key is the key of s_dao + s_tee, without either side holding it.
+        let expected =
+            VerifyingKey::from(&SigningKey::from_bytes(&(s_dao + s_tee).to_bytes()).unwrap());
+        assert_eq!(joint_key, expected);
+        assert!(expected.verify(message, &signature).is_ok());
+        assert!(expected.verify(b"another message", &signature).is_err());
+    }
+
+    #[test]
+    fn test_two_party_rejects_malformed_encryption_seed() {
+        let s = SecretScalar::new(Scalar::random(&mut OsRng));
+        let seed = SecretBytes::new(vec![7; ENCRYPTION_SEED_LEN - 1]);
+        assert!(two_party_sign(s.duplicate(), s, &seed, b"m", &mut OsRng).is_err());
+    }
+
     // --- Sui Utils Tests ---
     #[test]
     fn test_sui_address_generation() {
@@ -95,4 +404,2211 @@ mod tests {
         let hash = build_and_hash_sui_tx("0xSender", "0xRecipient", 100).unwrap();
         assert_eq!(hash.len(), 32); // Blake2b-256
     }
+
+    // --- Wallet Import Tests ---
+    #[test]
+    fn test_bech32_reference_vectors() {
+        // BIP-173 valid strings
+        for valid in [
+            "A12UEL5L",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+        ] {
+            let (hrp, data) = bech32_decode(valid).unwrap();
+            assert_eq!(
+                bech32_encode(&hrp, data.expose_secret()),
+                valid.to_lowercase()
+            );
+        }
+        // Bad checksum, mixed case, missing separator
+        assert!(bech32_decode("A12UEL5M").is_err());
+        assert!(bech32_decode("A12uEL5L").is_err());
+        assert!(bech32_decode("pzry9x0s0muk").is_err());
+    }
+
+    #[test]
+    fn test_import_sui_private_key() {
+        let key = SigningKey::random(&mut OsRng);
+        let raw = SecretBytes::new(key.to_bytes().to_vec());
+        let encoded = encode_sui_private_key(&raw).unwrap();
+        assert!(encoded.starts_with("suiprivkey1q"));
+
+        let from_bech32 = parse_secp256k1_private_key(&encoded).unwrap();
+        let from_hex =
+            parse_secp256k1_private_key(&format!("0x{}\n", hex::encode(key.to_bytes()))).unwrap();
+        assert_eq!(from_bech32, from_hex);
+        assert_eq!(from_bech32.expose_secret().to_bytes(), key.to_bytes());
+
+        // Ed25519 (flag 0x00) keys cannot back a secp256k1 vault.
+        let (_, data) = bech32_decode(&encoded).unwrap();
+        let mut ed25519 = data.expose_secret().to_vec();
+        ed25519[0] = 0x00;
+        assert!(parse_secp256k1_private_key(&bech32_encode("suiprivkey", &ed25519)).is_err());
+        assert!(parse_secp256k1_private_key(&hex::encode([0u8; 32])).is_err());
+        assert!(parse_secp256k1_private_key("not a key").is_err());
+    }
+
+    #[test]
+    fn test_split_existing_key_keeps_address() {
+        let key = SigningKey::random(&mut OsRng);
+        let original = pubkey_to_sui_address(&VerifyingKey::from(&key));
+        let secret = parse_secp256k1_private_key(&hex::encode(key.to_bytes())).unwrap();
+
+        let (s_dao, s_tee) = split_existing_key(&secret).unwrap();
+        let (_, pubkey_hex) =
+            mock_sign_and_verify(s_dao.expose_secret(), s_tee.expose_secret(), b"import").unwrap();
+        let joint = VerifyingKey::from_sec1_bytes(&hex::decode(pubkey_hex).unwrap()).unwrap();
+        assert_eq!(pubkey_to_sui_address(&joint), original);
+
+        let (other_dao, _) = split_existing_key(&secret).unwrap();
+        assert_ne!(other_dao.expose_secret(), s_dao.expose_secret());
+    }
+
+    // --- Break-Glass Export Tests ---
+    #[test]
+    fn test_break_glass_export_ceremony() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob", "Carol", "Dave"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members: keys.iter().map(|k| k.member().unwrap()).collect(),
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        assert_eq!(required_approvals(&group), 3);
+
+        let vault_key = SecretScalar::new(Scalar::random(&mut OsRng));
+        let vault_address = pubkey_to_sui_address(&VerifyingKey::from(
+            &SigningKey::from_bytes(&vault_key.expose_secret().to_bytes()).unwrap(),
+        ));
+        let recovery = SecretScalar::new(Scalar::random(&mut OsRng));
+        let recovery_pub = VerifyingKey::from(
+            &SigningKey::from_bytes(&recovery.expose_secret().to_bytes()).unwrap(),
+        );
+        let recovery_hex = hex::encode(recovery_pub.to_encoded_point(true).as_bytes());
+
+        let request =
+            ExportRequest::new(&vault_address, &recovery_hex, "wind-down", 1_000, 600).unwrap();
+        let digest = request.digest().unwrap();
+        let mut signatures = HashMap::new();
+        for k in &keys[..2] {
+            let address = k.member().unwrap().address;
+            signatures.insert(address, k.sign_personal_message(&digest).unwrap());
+        }
+
+        // Two of four is a majority of the threshold, not a super-majority.
+        assert!(export_vault_key(&group, &request, &signatures, &vault_key, 1_100).is_err());
+        let carol = &keys[2];
+        let carol_address = carol.member().unwrap().address;
+        signatures.insert(carol_address, carol.sign_personal_message(&digest).unwrap());
+
+        // Outside the dated window, or for a different vault, nothing is exported.
+        assert!(export_vault_key(&group, &request, &signatures, &vault_key, 1_601).is_err());
+        let other_key = SecretScalar::new(Scalar::random(&mut OsRng));
+        assert!(export_vault_key(&group, &request, &signatures, &other_key, 1_100).is_err());
+
+        let (bundle, record) =
+            export_vault_key(&group, &request, &signatures, &vault_key, 1_100).unwrap();
+        let mut approvers: Vec<&str> = record.approvals.iter().map(|a| a.member.as_str()).collect();
+        approvers.sort();
+        assert_eq!(approvers, ["Alice", "Bob", "Carol"]);
+        assert_eq!(record.request_digest, hex::encode(&digest));
+
+        assert_eq!(open_export(&bundle, &recovery).unwrap(), vault_key);
+        assert!(open_export(&bundle, &other_key).is_err());
+
+        // The ciphertext is bound to the approved request.
+        let mut tampered = bundle.clone();
+        tampered.request.reason = "something else".to_string();
+        assert!(open_export(&tampered, &recovery).is_err());
+    }
+
+    // --- Sui Identity Tests ---
+    #[test]
+    fn test_votes_are_sui_personal_messages_keyed_by_address() {
+        // Intent [PersonalMessage, V0, Sui] followed by the ULEB128-prefixed bytes.
+        let mut preimage = vec![3u8, 0, 0, 5];
+        preimage.extend_from_slice(b"hello");
+        assert_eq!(
+            personal_message_digest(b"hello").unwrap(),
+            Blake2b256::digest(&preimage).digest
+        );
+
+        let alice = MemberKey::generate("Alice");
+        let mallory = MemberKey::generate("Mallory");
+        let member = alice.member().unwrap();
+        let group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 1,
+            members: vec![member.clone()],
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        group.validate().unwrap();
+
+        let vote = alice.sign_personal_message(b"proposal").unwrap();
+        let raw = Base64::decode(&vote).unwrap();
+        assert_eq!((raw.len(), raw[0]), (98, 0x01));
+        assert_eq!(hex::encode(&raw[65..]), member.pubkey_hex);
+        assert_eq!(
+            verify_personal_message(b"proposal", &vote).unwrap(),
+            member.address
+        );
+        assert!(verify_personal_message(b"other", &vote).is_err());
+
+        let by_address = HashMap::from([(member.address.clone(), vote.clone())]);
+        assert!(
+            group
+                .verify_proposal(b"proposal", &by_address, ProposalKind::Transfer, 0)
+                .passed()
+        );
+        let by_name = HashMap::from([("Alice".to_string(), vote)]);
+        assert!(
+            !group
+                .verify_proposal(b"proposal", &by_name, ProposalKind::Transfer, 0)
+                .passed()
+        );
+
+        // A valid signature from another key does not count for Alice's address.
+        let forged = HashMap::from([(
+            member.address.clone(),
+            mallory.sign_personal_message(b"proposal").unwrap(),
+        )]);
+        assert!(
+            !group
+                .verify_proposal(b"proposal", &forged, ProposalKind::Transfer, 0)
+                .passed()
+        );
+
+        let mut tampered = group;
+        tampered.members[0].address = mallory.member().unwrap().address;
+        assert!(tampered.validate().is_err());
+    }
+
+    // --- Weighted Voting Tests ---
+    #[test]
+    fn test_weighted_quorum_and_governance() {
+        let keys: Vec<MemberKey> = ["Founder", "Bob", "Carol"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let mut group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members: keys
+                .iter()
+                .zip([3, 1, 1])
+                .map(|(k, weight)| Member {
+                    weight,
+                    ..k.member().unwrap()
+                })
+                .collect(),
+            quorum_weight: Some(4),
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        group.validate().unwrap();
+        assert_eq!((group.total_weight(), group.required_weight()), (5, 4));
+
+        let sign = |signers: &[&MemberKey], message: &[u8]| -> HashMap<String, String> {
+            signers
+                .iter()
+                .map(|k| {
+                    (
+                        k.member().unwrap().address,
+                        k.sign_personal_message(message).unwrap(),
+                    )
+                })
+                .collect()
+        };
+        let (founder, bob, carol) = (&keys[0], &keys[1], &keys[2]);
+
+        // Two heads are not enough without the founder's weight.
+        assert!(
+            !group
+                .verify_proposal(
+                    b"tx",
+                    &sign(&[bob, carol], b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+        assert!(
+            group
+                .verify_proposal(
+                    b"tx",
+                    &sign(&[founder, bob], b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+        assert_eq!(
+            group
+                .verify_proposal(
+                    b"tx",
+                    &sign(&[founder, bob, carol], b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .approved_weight,
+            5
+        );
+
+        // Raising Bob to 3 needs the current quorum, and the approval is single-use.
+        let action = GovernanceAction::SetWeight {
+            address: bob.member().unwrap().address,
+            weight: 3,
+        };
+        let digest = group.governance_digest(&action).unwrap();
+        assert!(
+            group
+                .apply_governance(&action, &sign(&[bob, carol], &digest), 0)
+                .is_err()
+        );
+        let approvals = sign(&[founder, carol], &digest);
+        group.apply_governance(&action, &approvals, 0).unwrap();
+        assert_eq!(group.total_weight(), 7);
+        assert!(
+            group
+                .verify_proposal(
+                    b"tx",
+                    &sign(&[bob, carol], b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+        assert!(group.apply_governance(&action, &approvals, 0).is_err());
+
+        // A quorum above the total weight is rejected and leaves the group as it was.
+        let unreachable = GovernanceAction::SetQuorumWeight { weight: 8 };
+        let digest = group.governance_digest(&unreachable).unwrap();
+        let before = group.membership_digest();
+        assert!(
+            group
+                .apply_governance(&unreachable, &sign(&[founder, bob], &digest), 0)
+                .is_err()
+        );
+        assert_eq!(group.membership_digest(), before);
+    }
+
+    #[test]
+    fn test_remove_rotate_and_threshold_governance() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob", "Carol"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let mut group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members: keys.iter().map(|k| k.member().unwrap()).collect(),
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        let approve = |group: &DaoGroup, action: &GovernanceAction, signers: &[&MemberKey]| {
+            approve_message(signers, &group.governance_digest(action).unwrap())
+        };
+        let (alice, bob, carol) = (&keys[0], &keys[1], &keys[2]);
+        let address = |k: &MemberKey| k.member().unwrap().address;
+
+        // Threshold must stay within 1..=members.
+        for bad in [0, 4] {
+            let action = GovernanceAction::SetThreshold { threshold: bad };
+            let approvals = approve(&group, &action, &[alice, bob]);
+            assert!(group.apply_governance(&action, &approvals, 1).is_err());
+        }
+        let action = GovernanceAction::SetThreshold { threshold: 3 };
+        let approvals = approve(&group, &action, &[alice, bob]);
+        group.apply_governance(&action, &approvals, 1).unwrap();
+        assert_eq!(group.threshold, 3);
+
+        // Removing a member below the threshold is rejected; after lowering it, allowed.
+        let remove = GovernanceAction::RemoveMember {
+            address: address(carol),
+        };
+        let approvals = approve(&group, &remove, &[alice, bob, carol]);
+        assert!(group.apply_governance(&remove, &approvals, 2).is_err());
+        let action = GovernanceAction::SetThreshold { threshold: 2 };
+        let approvals = approve(&group, &action, &[alice, bob, carol]);
+        group.apply_governance(&action, &approvals, 2).unwrap();
+        let approvals = approve(&group, &remove, &[alice, bob]);
+        let with_carol = group.membership_digest();
+        group.apply_governance(&remove, &approvals, 3).unwrap();
+        assert!(group.member(&address(carol)).is_none());
+
+        // Restoring the earlier membership does not revive its approvals.
+        let mut readded = group.clone();
+        readded.members.push(carol.member().unwrap());
+        assert_eq!(readded.membership_digest(), with_carol);
+        assert!(readded.apply_governance(&remove, &approvals, 4).is_err());
+
+        // Rotation moves Bob to the new key; the old key no longer counts and
+        // a duplicate key is refused.
+        let new_bob = MemberKey::generate("Bob");
+        let duplicate = GovernanceAction::RotateKey {
+            address: address(bob),
+            new_pubkey_hex: alice.member().unwrap().pubkey_hex,
+            new_scheme: SignatureScheme::Secp256k1,
+        };
+        let approvals = approve(&group, &duplicate, &[alice, bob]);
+        assert!(group.apply_governance(&duplicate, &approvals, 4).is_err());
+        let rotate = GovernanceAction::RotateKey {
+            address: address(bob),
+            new_pubkey_hex: new_bob.member().unwrap().pubkey_hex,
+            new_scheme: SignatureScheme::Secp256k1,
+        };
+        let approvals = approve(&group, &rotate, &[alice, bob]);
+        group.apply_governance(&rotate, &approvals, 4).unwrap();
+        let rotated = group.member_by_name("Bob").unwrap();
+        assert_eq!(rotated.address, address(&new_bob));
+        assert!(group.member(&address(bob)).is_none());
+        assert!(
+            !group
+                .verify_proposal(
+                    b"tx",
+                    &approve_message(&[alice, bob], b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+        assert!(
+            group
+                .verify_proposal(
+                    b"tx",
+                    &approve_message(&[alice, &new_bob], b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+
+        // Every applied change is recorded, in order, with its approvers.
+        let history: Vec<_> = group.history.iter().map(|r| r.applied_at).collect();
+        assert_eq!(history, vec![1, 2, 3, 4]);
+        assert_eq!(group.history[3].action, rotate);
+        assert_eq!(
+            group.history[3].approvers,
+            vec![address(alice), address(bob)]
+        );
+        assert_eq!(
+            group.history[3].membership_digest_after,
+            group.membership_digest()
+        );
+
+        // Admission needs the quorum; a member whose address does not match its
+        // key is refused.
+        let forged = GovernanceAction::AddMember {
+            member: Member {
+                address: address(alice),
+                ..carol.member().unwrap()
+            },
+        };
+        let approvals = approve(&group, &forged, &[alice, &new_bob]);
+        assert!(group.apply_governance(&forged, &approvals, 5).is_err());
+        let add = GovernanceAction::AddMember {
+            member: carol.member().unwrap(),
+        };
+        let approvals = approve(&group, &add, &[alice]);
+        assert!(group.apply_governance(&add, &approvals, 5).is_err());
+        let approvals = approve(&group, &add, &[alice, &new_bob]);
+        group.apply_governance(&add, &approvals, 5).unwrap();
+        assert!(group.member(&address(carol)).is_some());
+    }
+
+    fn approve_message(signers: &[&MemberKey], message: &[u8]) -> HashMap<String, String> {
+        signers
+            .iter()
+            .map(|k| {
+                (
+                    k.member().unwrap().address,
+                    k.sign_personal_message(message).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_roles_gate_proposals_and_approvals() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob", "Grace", "Audrey"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let roles = [
+            vec![Role::Proposer, Role::Voter],
+            vec![Role::Voter, Role::Executor],
+            vec![Role::Guardian],
+            vec![Role::Auditor],
+        ];
+        let mut group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members: keys
+                .iter()
+                .zip(roles)
+                .map(|(k, roles)| Member {
+                    roles: roles.into_iter().collect(),
+                    ..k.member().unwrap()
+                })
+                .collect(),
+            quorum_weight: None,
+            role_thresholds: [(Role::Guardian, 1)].into_iter().collect(),
+            vault_address: Some(
+                "0x0000000000000000000000000000000000000000000000000000000000000001".into(),
+            ),
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        group.validate().unwrap();
+        group.check_quorum_reachable().unwrap();
+        let (alice, bob, grace, audrey) = (&keys[0], &keys[1], &keys[2], &keys[3]);
+        let recipient = "0x0000000000000000000000000000000000000000000000000000000000000002";
+
+        // Only a proposer may draft, and a tampered draft fails verification.
+        let ledger = NonceLedger::default();
+        let create = |k: &MemberKey| {
+            TransferProposal::create(&group, k, recipient, 5, DEFAULT_CHAIN_ID, 1, u64::MAX)
+        };
+        assert!(create(bob).is_err());
+        let proposal = create(alice).unwrap();
+        proposal
+            .verify(&group, DEFAULT_CHAIN_ID, 0, &ledger)
+            .unwrap();
+        let tampered = TransferProposal {
+            amount: 6,
+            ..proposal.clone()
+        };
+        assert!(
+            tampered
+                .verify(&group, DEFAULT_CHAIN_ID, 0, &ledger)
+                .is_err()
+        );
+
+        let digest = proposal.vote_digest().unwrap();
+        let sign = |signers: &[&MemberKey], message: &[u8]| -> HashMap<String, String> {
+            signers
+                .iter()
+                .map(|k| {
+                    (
+                        k.member().unwrap().address,
+                        k.sign_personal_message(message).unwrap(),
+                    )
+                })
+                .collect()
+        };
+
+        // Voters reach the weight but the guardian is still missing; auditors never count.
+        let votes = sign(&[alice, bob, audrey], &digest);
+        let tally = group.verify_proposal(&digest, &votes, ProposalKind::Transfer, 0);
+        assert!(!tally.passed());
+        assert_eq!(tally.approved_weight, 2);
+        assert_eq!(tally.missing_roles, vec![(Role::Guardian, 0, 1)]);
+        assert_eq!(tally.non_voters.len(), 1);
+        assert!(
+            group
+                .verify_proposal(
+                    &digest,
+                    &sign(&[alice, bob, grace], &digest),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+
+        let address = |k: &MemberKey| k.member().unwrap().address;
+        assert!(
+            group
+                .require_permission(&address(bob), Permission::Execute)
+                .is_ok()
+        );
+        assert!(
+            group
+                .require_permission(&address(alice), Permission::Execute)
+                .is_err()
+        );
+        assert!(
+            group
+                .require_permission(&address(audrey), Permission::Vote)
+                .is_err()
+        );
+
+        // Dropping the guardian role would leave the threshold unreachable.
+        let action = GovernanceAction::SetRoles {
+            address: address(grace),
+            roles: [Role::Auditor].into_iter().collect(),
+        };
+        let gov = group.governance_digest(&action).unwrap();
+        let approvals = sign(&[alice, bob, grace], &gov);
+        assert!(group.apply_governance(&action, &approvals, 0).is_err());
+        assert!(group.member(&address(grace)).unwrap().can(Permission::Veto));
+    }
+
+    #[test]
+    fn test_vote_tally_reports_every_signature() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob", "Carol", "Audrey", "Mallory"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let (alice, bob, carol, audrey, mallory) =
+            (&keys[0], &keys[1], &keys[2], &keys[3], &keys[4]);
+        let address = |k: &MemberKey| k.member().unwrap().address;
+        // "Bobby" shares Bob's key; validate() would reject this group.
+        let group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 3,
+            members: vec![
+                alice.member().unwrap(),
+                bob.member().unwrap(),
+                Member {
+                    name: "Bobby".into(),
+                    ..bob.member().unwrap()
+                },
+                carol.member().unwrap(),
+                Member {
+                    roles: [Role::Auditor].into_iter().collect(),
+                    ..audrey.member().unwrap()
+                },
+            ],
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        assert!(group.validate().is_err());
+
+        let mut signatures = approve_message(&[alice, bob, audrey, mallory], b"tx");
+        signatures.insert(
+            address(carol),
+            mallory.sign_personal_message(b"tx").unwrap(),
+        );
+        let tally = group.verify_proposal(b"tx", &signatures, ProposalKind::Transfer, 0);
+        let names = |signers: &[TalliedSigner]| -> Vec<String> {
+            signers.iter().map(|s| s.name.clone()).collect()
+        };
+        assert_eq!(names(&tally.valid_voters), ["Alice", "Bob"]);
+        assert_eq!(names(&tally.duplicate_keys), ["Bobby"]);
+        assert_eq!(names(&tally.invalid_signatures), ["Carol"]);
+        assert_eq!(names(&tally.non_voters), ["Audrey"]);
+        assert_eq!(tally.unknown_signers, vec![address(mallory)]);
+        assert_eq!((tally.approved_weight, tally.required_weight), (2, 3));
+        assert!(!tally.passed());
+        assert_eq!(
+            tally.to_string(),
+            format!(
+                "2 of 3 required weight; invalid signature from Carol; \
+                 not counted (no vote role): Audrey; duplicate key: Bobby; unknown signer {}",
+                address(mallory)
+            )
+        );
+
+        // A shared key is counted once, so Bob cannot pass the vote alone with Alice.
+        let honest = approve_message(&[alice, bob, carol], b"tx");
+        assert!(
+            group
+                .verify_proposal(b"tx", &honest, ProposalKind::Transfer, 0)
+                .passed()
+        );
+        assert!(
+            !group
+                .verify_proposal(
+                    b"tx",
+                    &approve_message(&[alice, bob], b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+    }
+
+    #[test]
+    fn test_vote_delegation() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob", "Carol"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let (alice, bob, carol) = (&keys[0], &keys[1], &keys[2]);
+        let address = |k: &MemberKey| k.member().unwrap().address;
+        let mut group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members: keys.iter().map(|k| k.member().unwrap()).collect(),
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: Some(
+                "0x0000000000000000000000000000000000000000000000000000000000000001".into(),
+            ),
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        let launched = group.clone();
+        let delegate = |from: &MemberKey, to: &MemberKey, scope, expires_at| {
+            Delegation::create(&launched, from, &address(to), scope, expires_at).unwrap()
+        };
+        let to_bob = delegate(alice, bob, DelegationScope::Transfers, 100);
+
+        // Invalid certificates are refused.
+        let mut forged = to_bob.clone();
+        forged.signature = carol
+            .sign_personal_message(&to_bob.digest().unwrap())
+            .unwrap();
+        let other_dao = DaoGroup {
+            vault_address: Some("0x02".into()),
+            ..group.clone()
+        };
+        for (bad, now) in [
+            (forged, 0),
+            (delegate(alice, alice, DelegationScope::All, 100), 0),
+            (
+                Delegation::create(&other_dao, alice, &address(bob), DelegationScope::All, 100)
+                    .unwrap(),
+                0,
+            ),
+            (to_bob.clone(), 101),
+        ] {
+            assert!(group.clone().add_delegation(bad, now).is_err());
+        }
+        group.add_delegation(to_bob.clone(), 0).unwrap();
+        assert!(
+            group
+                .clone()
+                .add_delegation(delegate(alice, carol, DelegationScope::All, 100), 0)
+                .is_err()
+        );
+        group
+            .add_delegation(delegate(alice, carol, DelegationScope::Governance, 100), 0)
+            .unwrap();
+        // Not transitive: Carol's weight only counts if Alice signs herself.
+        group
+            .add_delegation(delegate(carol, alice, DelegationScope::Transfers, 100), 0)
+            .unwrap();
+
+        let by_bob = approve_message(&[bob], b"tx");
+        let tally = group.verify_proposal(b"tx", &by_bob, ProposalKind::Transfer, 50);
+        assert!(tally.passed());
+        assert_eq!(tally.approved_weight, 2);
+        assert_eq!(tally.delegated_votes.len(), 1);
+        assert!(tally.to_string().contains("delegated: Alice via Bob"));
+        for (kind, now) in [
+            (ProposalKind::Governance, 50),
+            (ProposalKind::BreakGlass, 50),
+            (ProposalKind::Transfer, 101),
+        ] {
+            assert!(!group.verify_proposal(b"tx", &by_bob, kind, now).passed());
+        }
+
+        // A delegator who also votes is counted once.
+        let both = approve_message(&[alice, bob], b"tx");
+        let tally = group.verify_proposal(b"tx", &both, ProposalKind::Transfer, 50);
+        assert_eq!(tally.approved_weight, 3);
+        assert_eq!(tally.delegated_votes.len(), 1);
+        assert_eq!(tally.delegated_votes[0].0.name, "Carol");
+
+        // Only the delegator can revoke, and a revoked certificate stays revoked.
+        let id = to_bob.id().unwrap();
+        let revocation = to_bob.revocation_digest().unwrap();
+        assert!(
+            group
+                .revoke_delegation(&id, &bob.sign_personal_message(&revocation).unwrap())
+                .is_err()
+        );
+        group
+            .revoke_delegation(&id, &alice.sign_personal_message(&revocation).unwrap())
+            .unwrap();
+        assert!(
+            !group
+                .verify_proposal(b"tx", &by_bob, ProposalKind::Transfer, 50)
+                .passed()
+        );
+        assert!(group.add_delegation(to_bob, 0).is_err());
+    }
+
+    #[test]
+    fn test_multisig_governance() {
+        let keys: Vec<MemberKey> = SignatureScheme::ALL
+            .iter()
+            .zip(["Alice", "Bob", "Carol"])
+            .map(|(scheme, name)| MemberKey::generate_scheme(name, *scheme))
+            .collect();
+        let mut members: Vec<Member> = keys.iter().map(|k| k.member().unwrap()).collect();
+        members[0].weight = 2;
+        let mut group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members,
+            quorum_weight: Some(3),
+            role_thresholds: Default::default(),
+            vault_address: Some(
+                "0x0000000000000000000000000000000000000000000000000000000000000001".into(),
+            ),
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: GovernanceMode::MultiSig,
+            timelock: Default::default(),
+        };
+        group.check_quorum_reachable().unwrap();
+        let multisig_pk = MultiSigPublicKey::from_group(&group).unwrap();
+        assert_eq!(multisig_pk.threshold, 3);
+        assert_eq!(
+            multisig_pk
+                .pk_map
+                .iter()
+                .map(|m| (m.scheme, m.weight))
+                .collect::<Vec<_>>(),
+            vec![
+                (SignatureScheme::Secp256k1, 2),
+                (SignatureScheme::Secp256r1, 1),
+                (SignatureScheme::Ed25519, 1),
+            ]
+        );
+        // Blake2b256(0x03 || threshold || (flag || pubkey || weight)*).
+        let mut preimage = vec![0x03, 3, 0];
+        for member in &group.members {
+            preimage.push(member.scheme.flag());
+            preimage.extend(hex::decode(&member.pubkey_hex).unwrap());
+            preimage.push(member.weight as u8);
+        }
+        assert_eq!(
+            multisig_pk.address(),
+            format!("0x{}", hex::encode(Blake2b256::digest(&preimage).digest))
+        );
+
+        let message = b"proposal";
+        let votes = |signers: &[&MemberKey]| -> HashMap<String, String> {
+            signers
+                .iter()
+                .map(|k| {
+                    (
+                        k.member().unwrap().address,
+                        k.sign_personal_message(message).unwrap(),
+                    )
+                })
+                .collect()
+        };
+        // Alice's weight 2 plus Carol's 1 meets the threshold; a stray vote
+        // from outside the group is left out.
+        let outsider = MemberKey::generate("Mallory");
+        let multisig = MultiSig::combine(
+            &multisig_pk,
+            message,
+            &votes(&[&keys[0], &keys[2], &outsider]),
+        )
+        .unwrap();
+        assert_eq!(multisig.bitmap, 0b101);
+        assert_eq!(multisig.weight(), 3);
+        let serialized = multisig.serialize();
+        assert_eq!(Base64::decode(&serialized).unwrap()[0], 0x03);
+        assert_eq!(MultiSig::parse(&serialized).unwrap(), multisig);
+        assert_eq!(
+            verify_personal_message(message, &serialized).unwrap(),
+            multisig_pk.address()
+        );
+        assert!(verify_personal_message(b"other", &serialized).is_err());
+
+        // Bob and Carol carry only 2 of 3.
+        assert!(MultiSig::combine(&multisig_pk, message, &votes(&[&keys[1], &keys[2]])).is_err());
+        // Claiming Bob's key for Carol's signature, or dropping a signer from
+        // the bitmap, is caught on verification.
+        let mut tampered = multisig.clone();
+        tampered.bitmap = 0b011;
+        assert!(tampered.verify(message).is_err());
+        let mut tampered = multisig.clone();
+        tampered.bitmap = 0b001;
+        assert!(tampered.verify(message).is_err());
+        let mut tampered = multisig;
+        tampered.sigs.pop();
+        tampered.bitmap = 0b001;
+        assert!(tampered.verify(message).is_err());
+
+        // Delegations and role thresholds have no MultiSig equivalent.
+        let delegation = Delegation::create(
+            &group,
+            &keys[1],
+            &keys[0].member().unwrap().address,
+            DelegationScope::All,
+            u64::MAX,
+        )
+        .unwrap();
+        assert!(group.add_delegation(delegation, 0).is_err());
+        let mut with_roles = group.clone();
+        with_roles.role_thresholds.insert(Role::Guardian, 1);
+        assert!(MultiSigPublicKey::from_group(&with_roles).is_err());
+        // Sui caps a MultiSig at ten keys.
+        for i in 0..8 {
+            group
+                .members
+                .push(MemberKey::generate(&format!("M{}", i)).member().unwrap());
+        }
+        assert!(group.check_quorum_reachable().is_err());
+    }
+
+    #[test]
+    fn test_spending_policy() {
+        let friend = "0x00000000000000000000000000000000000000000000000000000000000000aa";
+        let foe = "0x00000000000000000000000000000000000000000000000000000000000000bb";
+        let stranger = "0x00000000000000000000000000000000000000000000000000000000000000cc";
+        let policy: SpendingPolicy = serde_json::from_str(&format!(
+            r#"{{
+                "max_per_transaction": 1000,
+                "daily_limit": 1500,
+                "weekly_limit": 2400,
+                "allowlist": ["{}", "{}"],
+                "denylist": ["{}"],
+                "approval_tiers": [{{"above": 500, "extra_weight": 1}}, {{"above": 800, "extra_weight": 2}}]
+            }}"#,
+            friend.to_uppercase().replace("0X", "0x"),
+            stranger,
+            foe
+        ))
+        .unwrap();
+        policy.validate().unwrap();
+        assert!(serde_json::from_str::<SpendingPolicy>(r#"{"max_per_tx": 1}"#).is_err());
+        assert_eq!(policy.required_weight(500, 2), 2);
+        assert_eq!(policy.required_weight(501, 2), 3);
+        assert_eq!(policy.required_weight(900, 2), 4);
+
+        let now = 10 * DAY_SECS;
+        let mut ledger = SpendingLedger::default();
+        assert!(policy.evaluate(&ledger, friend, 400, 2, 2, now).is_empty());
+        assert_eq!(
+            policy.evaluate(&ledger, foe, 1200, 2, 2, now),
+            vec![
+                PolicyViolation::Denylisted(foe.into()),
+                PolicyViolation::NotAllowlisted(foe.into()),
+                PolicyViolation::TransactionCap {
+                    amount: 1200,
+                    cap: 1000
+                },
+                PolicyViolation::ApprovalWeight {
+                    above: 800,
+                    approved: 2,
+                    required: 4
+                },
+            ]
+        );
+        // Larger transfers pass once the extra approvals are there.
+        assert_eq!(policy.evaluate(&ledger, friend, 600, 2, 2, now).len(), 1);
+        assert!(policy.check(&ledger, friend, 600, 3, 2, now).is_ok());
+
+        // Rolling limits count spends inside the window only.
+        ledger.record(1, friend, 1000, now - WEEK_SECS);
+        ledger.record(2, friend, 900, now - 2 * DAY_SECS);
+        ledger.record(3, friend, 600, now - 60);
+        assert_eq!(ledger.spent_since(now - DAY_SECS), 600);
+        assert_eq!(ledger.spent_since(now - WEEK_SECS), 1500);
+        assert!(policy.check(&ledger, friend, 400, 2, 2, now).is_ok());
+        assert_eq!(
+            policy.evaluate(&ledger, friend, 1000, 4, 2, now),
+            vec![
+                PolicyViolation::DailyLimit {
+                    amount: 1000,
+                    spent: 600,
+                    limit: 1500
+                },
+                PolicyViolation::WeeklyLimit {
+                    amount: 1000,
+                    spent: 1500,
+                    limit: 2400
+                },
+            ]
+        );
+        let err = policy
+            .check(&ledger, friend, 1000, 4, 2, now)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("daily limit of 1500"), "{}", err);
+        assert!(err.contains("weekly limit of 2400"), "{}", err);
+        // A day later the 600 leaves the daily window.
+        assert!(
+            policy
+                .check(&ledger, friend, 900, 4, 2, now + DAY_SECS)
+                .is_ok()
+        );
+        // A spend whose signing failed is rolled back.
+        ledger.record(4, friend, 800, now);
+        ledger.unrecord(4);
+        assert_eq!(ledger.spent_since(now - DAY_SECS), 600);
+
+        // A lost ledger is rebuilt from the executions in the audit log; a
+        // proposal that was created but never executed does not count.
+        let mut log = AuditLog::default();
+        for id in [3, 5] {
+            log.append(
+                AuditEvent::ProposalCreated {
+                    id,
+                    proposer: "0xa".into(),
+                    vote_digest: String::new(),
+                    recipient: friend.into(),
+                    amount: 600,
+                },
+                now - 120,
+                None,
+            )
+            .unwrap();
+        }
+        log.append(
+            AuditEvent::ProposalExecuted {
+                id: 3,
+                executor: "0xa".into(),
+                tx_digest: String::new(),
+                signature: String::new(),
+            },
+            now - 60,
+            None,
+        )
+        .unwrap();
+        let mut rebuilt = SpendingLedger::default();
+        rebuilt.absorb_audit_log(&log);
+        assert_eq!(rebuilt.spent_since(now - DAY_SECS), 600);
+        ledger.absorb_audit_log(&log);
+        assert_eq!(ledger.spends.len(), 3);
+
+        // An empty policy allows anything the quorum approves.
+        assert!(
+            SpendingPolicy::default()
+                .evaluate(&ledger, foe, u64::MAX, 0, 2, now)
+                .is_empty()
+        );
+
+        let conflicting = SpendingPolicy {
+            allowlist: [foe.to_string()].into(),
+            denylist: [foe.to_uppercase()].into(),
+            ..Default::default()
+        };
+        assert!(conflicting.validate().is_err());
+    }
+
+    #[test]
+    fn test_proposal_votes_cannot_be_replayed() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let vault = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        let other_vault = "0x0000000000000000000000000000000000000000000000000000000000000009";
+        let recipient = "0x0000000000000000000000000000000000000000000000000000000000000002";
+        let group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members: keys.iter().map(|k| k.member().unwrap()).collect(),
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: Some(vault.into()),
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        let mut ledger = NonceLedger::default();
+
+        let nonce = ledger.issue(vault);
+        let first = TransferProposal::create(
+            &group,
+            &keys[0],
+            recipient,
+            100,
+            DEFAULT_CHAIN_ID,
+            nonce,
+            1_000,
+        )
+        .unwrap();
+        first
+            .verify(&group, DEFAULT_CHAIN_ID, 500, &ledger)
+            .unwrap();
+
+        // Votes cover the DAO, nonce and chain, not just the transaction.
+        let votes: HashMap<String, String> = keys
+            .iter()
+            .map(|k| {
+                (
+                    k.member().unwrap().address,
+                    k.sign_personal_message(&first.vote_digest().unwrap())
+                        .unwrap(),
+                )
+            })
+            .collect();
+        assert!(
+            group
+                .verify_proposal(
+                    &first.vote_digest().unwrap(),
+                    &votes,
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+        assert!(
+            !group
+                .verify_proposal(
+                    &first.tx_digest().unwrap(),
+                    &votes,
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+        // Proposing is not voting: Alice's proposer signature does not count.
+        let mut proposer_as_vote = votes.clone();
+        proposer_as_vote.insert(
+            keys[0].member().unwrap().address,
+            first.proposer_signature.clone(),
+        );
+        assert!(
+            !group
+                .verify_proposal(
+                    &first.vote_digest().unwrap(),
+                    &proposer_as_vote,
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+
+        // Expired, wrong chain, or another DAO's proposal.
+        assert!(
+            first
+                .verify(&group, DEFAULT_CHAIN_ID, 1_001, &ledger)
+                .is_err()
+        );
+        assert!(first.verify(&group, "sui:testnet", 500, &ledger).is_err());
+        let cross_dao = DaoGroup {
+            vault_address: Some(other_vault.into()),
+            ..group.clone()
+        };
+        assert!(
+            first
+                .verify(&cross_dao, DEFAULT_CHAIN_ID, 500, &ledger)
+                .is_err()
+        );
+
+        // An identical transfer gets a fresh nonce, so the old votes do not carry over.
+        ledger.consume(vault, first.header.nonce).unwrap();
+        assert!(
+            first
+                .verify(&group, DEFAULT_CHAIN_ID, 500, &ledger)
+                .is_err()
+        );
+        assert!(ledger.consume(vault, first.header.nonce).is_err());
+        let second = TransferProposal::create(
+            &group,
+            &keys[0],
+            recipient,
+            100,
+            DEFAULT_CHAIN_ID,
+            ledger.issue(vault),
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(second.header.nonce, 2);
+        assert_eq!(second.tx_digest().unwrap(), first.tx_digest().unwrap());
+        assert!(
+            !group
+                .verify_proposal(
+                    &second.vote_digest().unwrap(),
+                    &votes,
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+
+        // The ledger is per DAO.
+        assert_eq!(ledger.issue(other_vault), 1);
+        let header = Proposal {
+            dao_id: other_vault.into(),
+            ..first.header.clone()
+        };
+        header
+            .check(other_vault, DEFAULT_CHAIN_ID, 500, &ledger)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_nonce_ledger_tracks_each_nonce() {
+        let vault = "0x01";
+        let mut ledger = NonceLedger::default();
+        let (n1, n2) = (ledger.issue(vault), ledger.issue(vault));
+        ledger.consume(vault, n2).unwrap();
+        assert!(ledger.consume(vault, n2).is_err());
+        ledger.consume(vault, n1).unwrap();
+        assert_eq!(ledger.issue(vault), 3);
+
+        // A lost ledger is rebuilt from the audit log.
+        let mut log = AuditLog::default();
+        for nonce in [n1, n2] {
+            log.append(
+                AuditEvent::NonceConsumed {
+                    dao_id: vault.into(),
+                    nonce,
+                },
+                1,
+                None,
+            )
+            .unwrap();
+        }
+        let mut rebuilt = NonceLedger::default();
+        rebuilt.absorb_audit_log(&log);
+        assert!(rebuilt.check_unused(vault, n1).is_err());
+        assert!(rebuilt.check_unused(vault, n2).is_err());
+        assert_eq!(rebuilt.issue(vault), 3);
+
+        // Older ledgers only kept the highest executed nonce.
+        let legacy: NonceLedger =
+            serde_json::from_str(r#"{"issued":{"0x01":5},"consumed":{"0x01":4}}"#).unwrap();
+        assert!(legacy.check_unused(vault, 3).is_err());
+        legacy.check_unused(vault, 5).unwrap();
+    }
+
+    #[test]
+    fn test_proposal_store_follows_move_lifecycle() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob", "Carol"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let vault = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        let recipient = "0x0000000000000000000000000000000000000000000000000000000000000002";
+        let group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members: keys.iter().map(|k| k.member().unwrap()).collect(),
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: Some(vault.into()),
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        let mut ledger = NonceLedger::default();
+        let mut store = ProposalStore::default();
+        let mut propose = |ledger: &mut NonceLedger, expires_at: u64| {
+            let proposal = TransferProposal::create(
+                &group,
+                &keys[0],
+                recipient,
+                10,
+                DEFAULT_CHAIN_ID,
+                ledger.issue(vault),
+                expires_at,
+            )
+            .unwrap();
+            store.add(proposal, 0).unwrap()
+        };
+        let first = propose(&mut ledger, 1_000);
+        let second = propose(&mut ledger, 1_000);
+        let third = propose(&mut ledger, 50);
+        let vote = |store: &mut ProposalStore, id: u64, k: &MemberKey| {
+            let digest = store.get(id).unwrap().proposal.vote_digest().unwrap();
+            store.record_vote(
+                &group,
+                id,
+                &k.member().unwrap().address,
+                &k.sign_personal_message(&digest).unwrap(),
+                1,
+            )
+        };
+
+        // Active until the quorum is reached; one vote per member.
+        assert_eq!(
+            vote(&mut store, second, &keys[0]).unwrap(),
+            ProposalStatus::Active
+        );
+        assert!(vote(&mut store, second, &keys[0]).is_err());
+        let forged = keys[2]
+            .sign_personal_message(&store.get(second).unwrap().proposal.vote_digest().unwrap())
+            .unwrap();
+        assert!(
+            store
+                .record_vote(
+                    &group,
+                    second,
+                    &keys[1].member().unwrap().address,
+                    &forged,
+                    1
+                )
+                .is_err()
+        );
+        assert_eq!(
+            vote(&mut store, second, &keys[1]).unwrap(),
+            ProposalStatus::Passed
+        );
+
+        // Extra approvals are still taken until execution.
+        assert_eq!(
+            vote(&mut store, second, &keys[2]).unwrap(),
+            ProposalStatus::Passed
+        );
+        assert_eq!(store.get(second).unwrap().votes.len(), 3);
+
+        // The executor authorizes under its own domain; a vote does not count.
+        let passed = store.get(second).unwrap().proposal.clone();
+        let executor = keys[1].member().unwrap().address;
+        let authorization = keys[1]
+            .sign_personal_message(&passed.execute_digest().unwrap())
+            .unwrap();
+        passed
+            .verify_execution(&group, &executor, &authorization)
+            .unwrap();
+        let vote_sig = &store.get(second).unwrap().votes[&executor].signature;
+        assert!(
+            passed
+                .verify_execution(&group, &executor, vote_sig)
+                .is_err()
+        );
+        let by_alice = keys[0]
+            .sign_personal_message(&passed.execute_digest().unwrap())
+            .unwrap();
+        assert!(
+            passed
+                .verify_execution(&group, &executor, &by_alice)
+                .is_err()
+        );
+
+        // Only a passed proposal executes, once.
+        let execution = ExecutionRecord {
+            executor,
+            authorization,
+            executed_at: 2,
+            tx_digest: "00".into(),
+            signature: "00".into(),
+            multisig: None,
+        };
+        assert!(store.record_execution(first, execution.clone()).is_err());
+        store.record_execution(second, execution.clone()).unwrap();
+        assert!(store.record_execution(second, execution).is_err());
+        assert!(vote(&mut store, second, &keys[2]).is_err());
+        ledger.consume(vault, second).unwrap();
+
+        // A lower nonce stays open after a higher one executes; the
+        // short-lived proposal expires.
+        assert_eq!(store.sweep(100, &ledger), vec![third]);
+        assert_eq!(store.get(first).unwrap().status, ProposalStatus::Active);
+        ledger.check_unused(vault, first).unwrap();
+        assert!(ledger.check_unused(vault, second).is_err());
+        assert!(vote(&mut store, third, &keys[1]).is_err());
+
+        // Status is stored with the Move codes.
+        let json: serde_json::Value = serde_json::to_value(&store).unwrap();
+        let codes: Vec<u64> = [first, second, third]
+            .iter()
+            .map(|id| {
+                json["proposals"][id.to_string()]["status"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(codes, vec![0, 3, 2]);
+        assert_eq!(
+            "1".parse::<ProposalStatus>().unwrap(),
+            ProposalStatus::Passed
+        );
+        assert!(ProposalStatus::try_from(4).is_err());
+    }
+
+    #[test]
+    fn test_timelock_and_veto() {
+        let keys: Vec<MemberKey> = ["Alice", "Bob", "Grace", "Gus"]
+            .iter()
+            .map(|n| MemberKey::generate(n))
+            .collect();
+        let vault = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        let recipient = "0x0000000000000000000000000000000000000000000000000000000000000002";
+        let mut members: Vec<Member> = keys.iter().map(|k| k.member().unwrap()).collect();
+        for guardian in &mut members[2..] {
+            guardian.roles = [Role::Guardian].into();
+        }
+        let mut group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 2,
+            members,
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: Some(vault.into()),
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Timelock {
+                delay_secs: 100,
+                veto_threshold: 2,
+            },
+        };
+        group.validate().unwrap();
+        let mut ledger = NonceLedger::default();
+        let mut store = ProposalStore::default();
+        let mut propose = |store: &mut ProposalStore| {
+            let proposal = TransferProposal::create(
+                &group,
+                &keys[0],
+                recipient,
+                10,
+                DEFAULT_CHAIN_ID,
+                ledger.issue(vault),
+                1_000,
+            )
+            .unwrap();
+            store.add(proposal, 0).unwrap()
+        };
+        let (first, second) = (propose(&mut store), propose(&mut store));
+        let sign = |store: &ProposalStore, id: u64, k: &MemberKey, veto: bool| {
+            let proposal = &store.get(id).unwrap().proposal;
+            let digest = if veto {
+                proposal.veto_digest().unwrap()
+            } else {
+                proposal.vote_digest().unwrap()
+            };
+            (
+                k.member().unwrap().address,
+                k.sign_personal_message(&digest).unwrap(),
+            )
+        };
+        let vote = |store: &mut ProposalStore, id: u64, k: &MemberKey, now: u64| {
+            let (address, signature) = sign(store, id, k, false);
+            store.record_vote(&group, id, &address, &signature, now)
+        };
+        let veto = |store: &mut ProposalStore, id: u64, k: &MemberKey, now: u64| {
+            let (address, signature) = sign(store, id, k, true);
+            store.record_veto(&group, id, &address, &signature, now)
+        };
+
+        // Passing starts the timelock; execution waits for it.
+        assert!(veto(&mut store, first, &keys[2], 5).is_err());
+        vote(&mut store, first, &keys[0], 5).unwrap();
+        assert_eq!(
+            vote(&mut store, first, &keys[1], 10).unwrap(),
+            ProposalStatus::Passed
+        );
+        let record = store.get(first).unwrap();
+        assert_eq!(record.executable_at, Some(110));
+        assert!(record.check_executable(&group, 109).is_err());
+        record.check_executable(&group, 110).unwrap();
+
+        // Only guardians veto, over the veto digest, once each.
+        assert!(veto(&mut store, first, &keys[0], 20).is_err());
+        let (address, vote_signature) = sign(&store, first, &keys[2], false);
+        assert!(
+            store
+                .record_veto(&group, first, &address, &vote_signature, 20)
+                .is_err()
+        );
+        assert_eq!(
+            veto(&mut store, first, &keys[2], 20).unwrap(),
+            ProposalStatus::Passed
+        );
+        assert!(veto(&mut store, first, &keys[2], 21).is_err());
+        // One veto is below the threshold of two; the window closes at 110.
+        store
+            .get(first)
+            .unwrap()
+            .check_executable(&group, 110)
+            .unwrap();
+        assert!(veto(&mut store, first, &keys[3], 110).is_err());
+
+        // A second guardian fails the proposal.
+        vote(&mut store, second, &keys[0], 10).unwrap();
+        vote(&mut store, second, &keys[1], 10).unwrap();
+        veto(&mut store, second, &keys[2], 50).unwrap();
+        assert_eq!(
+            veto(&mut store, second, &keys[3], 60).unwrap(),
+            ProposalStatus::Failed
+        );
+        let record = store.get(second).unwrap();
+        let failure = record.failure.as_deref().unwrap();
+        assert!(
+            failure.starts_with("vetoed by")
+                && failure.contains("Grace")
+                && failure.contains("Gus")
+        );
+        assert!(record.check_executable(&group, 500).is_err());
+
+        // Shard approval starts the timelock when it is first presented.
+        let third = propose(&mut store);
+        store.mark_passed(&group, third, 200).unwrap();
+        store.mark_passed(&group, third, 250).unwrap();
+        assert_eq!(store.get(third).unwrap().executable_at, Some(300));
+
+        // Changing the timelock changes the membership digest; the default does not.
+        let before = group.membership_digest();
+        group.timelock = Timelock::default();
+        assert_ne!(group.membership_digest(), before);
+        group.timelock.veto_threshold = 0;
+        assert!(group.validate().is_err());
+    }
+
+    // --- Move Contract Model Tests (mirror move/sovereign_dao/tests/dao_tests.move) ---
+    fn move_addr(n: u8) -> String {
+        format!("0x{:064x}", n)
+    }
+    const ENCRYPTION_ID: [u8; 3] = [0x12, 0x34, 0x56];
+
+    #[test]
+    fn test_move_dao_lifecycle() {
+        let (m1, m2, m3, tee) = (
+            move_addr(0xA),
+            move_addr(0xB),
+            move_addr(0xC),
+            move_addr(0xE),
+        );
+        let mut chain = SovereignDao::new();
+
+        // 1. Create DAO (threshold 2); 2. proposal to add the TEE (action 1)
+        let dao = chain.create_dao(
+            "MyDAO",
+            &[m1.clone(), m2.clone(), m3.clone()],
+            2,
+            &ENCRYPTION_ID,
+        );
+        let add_tee = chain
+            .create_proposal(&m1, dao, "Add TEE", "Desc", ACTION_ADD_TEE, &tee)
+            .unwrap();
+
+        // 3. Members 1 and 2 vote; the second vote passes it
+        chain.vote(&m1, dao, add_tee).unwrap();
+        assert_eq!(proposal_status(chain.proposal(add_tee).unwrap()), 0);
+        chain.vote(&m2, dao, add_tee).unwrap();
+        assert_eq!(proposal_status(chain.proposal(add_tee).unwrap()), 1);
+
+        // 4. Execute: the TEE becomes trusted
+        assert!(!is_trusted_tee(chain.dao(dao).unwrap(), &tee));
+        chain.execute_proposal(&m1, dao, add_tee).unwrap();
+        assert!(is_trusted_tee(chain.dao(dao).unwrap(), &tee));
+        assert_eq!(proposal_status(chain.proposal(add_tee).unwrap()), 3);
+
+        // 5-6. A generic signing proposal, passed by members 1 and 3. Unlike the
+        // Move test, ids are explicit, so there is no ambiguity between proposals.
+        let sign_x = chain
+            .create_proposal(&m1, dao, "Sign X", "Desc", ACTION_NONE, &move_addr(0))
+            .unwrap();
+        chain.vote(&m1, dao, sign_x).unwrap();
+        chain.vote(&m3, dao, sign_x).unwrap();
+        assert_eq!(proposal_status(chain.proposal(sign_x).unwrap()), 1);
+
+        // 7. Seal approve by the trusted TEE
+        chain
+            .seal_approve(&tee, &ENCRYPTION_ID, dao, sign_x)
+            .unwrap();
+        assert_eq!(
+            chain.events.last(),
+            Some(&Event::Voted {
+                proposal_id: sign_x,
+                voter: m3.clone()
+            })
+        );
+        assert_eq!(chain.events.len(), 7);
+    }
+
+    #[test]
+    fn test_move_seal_approve_untrusted_tee() {
+        let m1 = move_addr(0xA);
+        let trace = vec![
+            (
+                m1.clone(),
+                Call::CreateDao {
+                    name: "MyDAO".into(),
+                    members: vec![m1.clone()],
+                    threshold: 1,
+                    encryption_id: ENCRYPTION_ID.to_vec(),
+                },
+            ),
+            (
+                m1.clone(),
+                Call::CreateProposal {
+                    dao: 1,
+                    title: "P".into(),
+                    description: "D".into(),
+                    action_type: ACTION_NONE,
+                    action_target: move_addr(0),
+                },
+            ),
+            (
+                m1.clone(),
+                Call::Vote {
+                    dao: 1,
+                    proposal: 2,
+                },
+            ),
+            (
+                move_addr(0xD),
+                Call::SealApprove {
+                    id: ENCRYPTION_ID.to_vec(),
+                    dao: 1,
+                    proposal: 2,
+                },
+            ),
+        ];
+        let results = SovereignDao::new().run(&trace);
+        assert_eq!(
+            results,
+            vec![
+                Ok(Some(1)),
+                Ok(Some(2)),
+                Ok(None),
+                Err(MoveAbort::NotTrustedTee)
+            ]
+        );
+        assert_eq!(MoveAbort::NotTrustedTee.code(), 3);
+    }
+
+    fn abort<T: std::fmt::Debug>(result: Result<T, MoveAbort>) -> u64 {
+        result.unwrap_err().code()
+    }
+
+    #[test]
+    fn test_move_abort_codes() {
+        let (m1, m2, outsider, tee) = (
+            move_addr(0xA),
+            move_addr(0xB),
+            move_addr(0xD),
+            move_addr(0xE),
+        );
+        let mut chain = SovereignDao::new();
+        let dao = chain.create_dao(
+            "A",
+            &[m1.clone(), m2.clone(), m1.clone()],
+            2,
+            &ENCRYPTION_ID,
+        );
+        let other = chain.create_dao("B", std::slice::from_ref(&m1), 1, b"other");
+        assert_eq!(chain.dao(dao).unwrap().members.len(), 2);
+
+        assert_eq!(
+            abort(chain.create_proposal(&outsider, dao, "t", "d", 0, &m1)),
+            0
+        );
+        let p = chain
+            .create_proposal(&m1, dao, "t", "d", ACTION_ADD_TEE, &tee)
+            .unwrap();
+        assert_eq!(abort(chain.vote(&outsider, dao, p)), 0);
+        assert_eq!(abort(chain.execute_proposal(&m1, dao, p)), 1);
+        assert_eq!(abort(chain.seal_approve(&tee, &ENCRYPTION_ID, dao, p)), 1);
+        assert_eq!(abort(chain.vote(&m1, other, p)), 2);
+        chain.vote(&m1, dao, p).unwrap();
+        assert_eq!(abort(chain.vote(&m1, dao, p)), 4);
+        chain.vote(&m2, dao, p).unwrap();
+        chain.execute_proposal(&outsider, dao, p).unwrap();
+        assert_eq!(abort(chain.execute_proposal(&m1, dao, p)), 1);
+        // A member who has not voted still gets EProposalExecuted on a closed proposal.
+        let late = chain.create_dao("C", &[m1.clone(), m2.clone(), outsider.clone()], 2, b"c");
+        let q = chain.create_proposal(&m1, late, "t", "d", 0, &m1).unwrap();
+        chain.vote(&m1, late, q).unwrap();
+        chain.vote(&m2, late, q).unwrap();
+        assert_eq!(abort(chain.vote(&outsider, late, q)), 5);
+
+        // Executed proposals still authorise Seal; wrong id or untrusted sender do not.
+        chain.seal_approve(&tee, &ENCRYPTION_ID, dao, p).unwrap();
+        assert_eq!(abort(chain.seal_approve(&tee, b"nope", dao, p)), 6);
+        assert_eq!(abort(chain.seal_approve(&m1, &ENCRYPTION_ID, dao, p)), 3);
+
+        // Removing the TEE revokes it.
+        let remove = chain
+            .create_proposal(&m1, dao, "t", "d", ACTION_REMOVE_TEE, &tee)
+            .unwrap();
+        chain.vote(&m1, dao, remove).unwrap();
+        chain.vote(&m2, dao, remove).unwrap();
+        chain.execute_proposal(&m1, dao, remove).unwrap();
+        assert_eq!(abort(chain.seal_approve(&tee, &ENCRYPTION_ID, dao, p)), 3);
+
+        // Unknown ids (or an id of the wrong kind) fail instead of panicking.
+        let missing = 999;
+        assert_eq!(
+            abort(chain.create_proposal(&m1, missing, "t", "d", 0, &m1)),
+            100
+        );
+        assert_eq!(abort(chain.vote(&m1, dao, missing)), 100);
+        assert_eq!(abort(chain.vote(&m1, p, p)), 100);
+        assert_eq!(abort(chain.execute_proposal(&m1, missing, p)), 100);
+        assert_eq!(
+            abort(chain.seal_approve(&tee, &ENCRYPTION_ID, dao, missing)),
+            100
+        );
+    }
+
+    // --- Keystore Tests ---
+    #[test]
+    fn test_member_names_cannot_leave_keystore_dir() {
+        assert_eq!(
+            keystore_path("keystore", "Alice_2-b").unwrap(),
+            std::path::Path::new("keystore/Alice_2-b.json")
+        );
+        for name in ["../../x", "a/b", "..", "", "Alice Smith", "Alice.pending"] {
+            assert!(keystore_path("keystore", name).is_err(), "{:?}", name);
+        }
+
+        let group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 1,
+            members: vec![Member {
+                name: "../Alice".to_string(),
+                ..MemberKey::generate("Alice").member().unwrap()
+            }],
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        assert!(group.validate().is_err());
+    }
+
+    #[test]
+    fn test_keystore_unlocks_only_with_passphrase() {
+        let key = MemberKey::generate("Alice");
+        let member = key.member().unwrap();
+        let keystore =
+            MemberKeystore::encrypt_with_params(&key, b"correct horse", 64, 1, 1, &mut OsRng)
+                .unwrap();
+
+        // The file carries the public half only.
+        let json = serde_json::to_string(&keystore).unwrap();
+        assert!(!json.contains(&hex::encode(key.secret().expose_secret())));
+        assert_eq!(keystore.member().unwrap(), member);
+
+        let unlocked = keystore.unlock(b"correct horse").unwrap();
+        assert_eq!(
+            unlocked.secret().expose_secret(),
+            key.secret().expose_secret()
+        );
+        let group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 1,
+            members: vec![member.clone()],
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        let signatures = HashMap::from([(
+            member.address.clone(),
+            unlocked.sign_personal_message(b"m").unwrap(),
+        )]);
+        assert!(
+            group
+                .verify_proposal(b"m", &signatures, ProposalKind::Transfer, 0)
+                .passed()
+        );
+
+        assert!(keystore.unlock(b"wrong").is_err());
+        let mut renamed = keystore.clone();
+        renamed.name = "Mallory".to_string();
+        assert!(renamed.unlock(b"correct horse").is_err());
+        let mut weakened = keystore.clone();
+        weakened.kdf.t_cost = 2;
+        assert!(weakened.unlock(b"correct horse").is_err());
+        assert!(MemberKeystore::encrypt(&key, b"").is_err());
+    }
+
+    #[test]
+    fn test_member_key_schemes() {
+        // RFC 8032 test 1 seed; Sui address = Blake2b256(0x00 || pubkey).
+        let seed = hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+            .unwrap();
+        let rfc = MemberKey::from_bytes("Ed", SignatureScheme::Ed25519, &seed)
+            .unwrap()
+            .member()
+            .unwrap();
+        assert_eq!(
+            rfc.pubkey_hex,
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(
+            rfc.address,
+            "0x304af458e90e97c841685b8cbbc59b909f3e2cf150df590ada4c81452c29737d"
+        );
+
+        let keys: Vec<MemberKey> = SignatureScheme::ALL
+            .iter()
+            .zip(["Alice", "Bob", "Carol"])
+            .map(|(scheme, name)| MemberKey::generate_scheme(name, *scheme))
+            .collect();
+        for key in &keys {
+            let member = key.member().unwrap();
+            assert_eq!(member.scheme, key.scheme);
+            let pubkey = hex::decode(&member.pubkey_hex).unwrap();
+            assert_eq!(pubkey.len(), key.scheme.pubkey_len());
+            assert_eq!(
+                member.address,
+                public_key_to_sui_address(key.scheme, &pubkey)
+            );
+
+            let vote = key.sign_personal_message(b"proposal").unwrap();
+            assert_eq!(
+                verify_personal_message(b"proposal", &vote).unwrap(),
+                member.address
+            );
+            assert!(verify_personal_message(b"other", &vote).is_err());
+            // Relabeling the signature with another scheme's flag never verifies.
+            let mut bytes = Base64::decode(&vote).unwrap();
+            assert_eq!(bytes[0], key.scheme.flag());
+            for other in SignatureScheme::ALL.iter().filter(|s| **s != key.scheme) {
+                bytes[0] = other.flag();
+                assert!(verify_personal_message(b"proposal", &Base64::encode(&bytes)).is_err());
+            }
+        }
+
+        // A mixed-scheme group votes, and a member can rotate to another scheme.
+        let mut group = DaoGroup {
+            schema_version: GROUP_SCHEMA_VERSION,
+            threshold: 3,
+            members: keys.iter().map(|k| k.member().unwrap()).collect(),
+            quorum_weight: None,
+            role_thresholds: Default::default(),
+            vault_address: None,
+            tee_address: None,
+            history: Vec::new(),
+            delegations: Vec::new(),
+            revoked_delegations: Default::default(),
+            governance_mode: Default::default(),
+            timelock: Default::default(),
+        };
+        group.validate().unwrap();
+        let signers: Vec<&MemberKey> = keys.iter().collect();
+        assert!(
+            group
+                .verify_proposal(
+                    b"tx",
+                    &approve_message(&signers, b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+        let passkey = MemberKey::generate_scheme("Alice", SignatureScheme::Secp256r1);
+        let rotate = GovernanceAction::RotateKey {
+            address: keys[0].member().unwrap().address,
+            new_pubkey_hex: passkey.member().unwrap().pubkey_hex,
+            new_scheme: SignatureScheme::Secp256r1,
+        };
+        let approvals = approve_message(&signers, &group.governance_digest(&rotate).unwrap());
+        group.apply_governance(&rotate, &approvals, 0).unwrap();
+        assert_eq!(group.members[0], passkey.member().unwrap());
+        let signers = [&passkey, &keys[1], &keys[2]];
+        assert!(
+            group
+                .verify_proposal(
+                    b"tx",
+                    &approve_message(&signers, b"tx"),
+                    ProposalKind::Transfer,
+                    0
+                )
+                .passed()
+        );
+
+        // Keystores remember the scheme; a relabeled one does not unlock.
+        let keystore =
+            MemberKeystore::encrypt_with_params(&keys[2], b"pw", 64, 1, 1, &mut OsRng).unwrap();
+        assert_eq!(keystore.scheme, SignatureScheme::Ed25519);
+        assert_eq!(
+            keystore.unlock(b"pw").unwrap().member().unwrap(),
+            keys[2].member().unwrap()
+        );
+        let mut relabeled = keystore.clone();
+        relabeled.scheme = SignatureScheme::Secp256k1;
+        assert!(relabeled.unlock(b"pw").is_err());
+    }
+
+    // --- Regression Vectors (docs/test_vectors.md) ---
+    #[test]
+    fn test_regression_vectors() {
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+
+        let (s_dao, s_tee) = generate_initial_shares_with_rng(&mut rng).unwrap();
+        let dao = bytes_to_scalar(s_dao.expose_secret()).unwrap();
+        let tee = bytes_to_scalar(s_tee.expose_secret()).unwrap();
+        let shards = split_secret_with_rng(&dao, 2, 3, &mut rng);
+        let alice = MemberKey::generate_with_rng("Alice", &mut rng);
+
+        let key = dao.expose_secret() + tee.expose_secret();
+        let vault = VerifyingKey::from(&SigningKey::from_bytes(&key.to_bytes()).unwrap());
+        let address = pubkey_to_sui_address(&vault);
+        let digest = build_and_hash_sui_tx(&address, VECTOR_RECIPIENT, 100).unwrap();
+        let vote = alice.sign_personal_message(&digest).unwrap();
+        let seed = SecretBytes::new(vec![7; ENCRYPTION_SEED_LEN]);
+        let (signature, _) = two_party_sign(dao, tee, &seed, &digest, &mut rng).unwrap();
+
+        assert_eq!(
+            hex::encode(s_dao.expose_secret()),
+            "20f400927857aaf64114f561baacb379708c79a1dc1476ab573216a4020764bde5"
+        );
+        assert_eq!(
+            hex::encode(s_tee.expose_secret()),
+            "2045c143dbb9609c22ab855d60925a997603d78a3f20d154abd8ddfa6974eedf6b"
+        );
+        let shard_hex: Vec<String> = shards
+            .iter()
+            .map(|(_, s)| hex::encode(s.expose_secret().to_bytes()))
+            .collect();
+        assert_eq!(
+            shard_hex,
+            [
+                "2b84bb84b815fb3ed56765bb76155cd888b4d9c9772ad0595c938a3ef27bf0ac",
+                "6308e4911881003c95d969bc3f77403f3f9eee9d8927959746e2cf08adc964b4",
+                "9a8d0d9d78ec053a564b6dbd08d923a5f68903719b245ad5313213d26916d8bc",
+            ]
+        );
+        assert_eq!(
+            alice.member().unwrap().pubkey_hex,
+            "039541d5189f6f745b8b873c3ea0aa18b3bd7a6e7a348b384209a69b42bcc1dea0"
+        );
+        assert_eq!(
+            alice.member().unwrap().address,
+            "0x915e96a023f95f5b24aa88e663ec2aff8ff413e853691eb1bdfa48efd21012cd"
+        );
+        assert_eq!(
+            address,
+            "0x4cbe812e160f1373bd1d092e2ae9998c6afd415ce7b58b489cdc519f12a2b4f4"
+        );
+        assert_eq!(
+            hex::encode(&digest),
+            "d208569f4ac234f83693a029f0ee607901cdd697398219e296accfa9f49e8027"
+        );
+        assert_eq!(
+            vote,
+            "AYNrHbHl8jk2ynyyibTvvDgF2avgG5ftWCUV9MxwpGYuaRacQs6Felhg2KcNz71pT31g63u5CiIeY4GKVZ7UsPkDlUHVGJ9vdFuLhzw+oKoYs716bno0izhCCaabQrzB3qA="
+        );
+        assert_eq!(
+            verify_personal_message(&digest, &vote).unwrap(),
+            alice.member().unwrap().address
+        );
+        // 2PC-MPC signatures are randomized; only their validity is pinned.
+        assert!(vault.verify(&digest, &signature).is_ok());
+    }
+
+    // A share pair as builds before the share codec wrote it, and the vault
+    // address those builds derived from it. `share-migrate` must keep it.
+    const LEGACY_DAO_SHARE: &str =
+        "20f400927857aaf64114f561baacb379708c79a1dc1476ab573216a4020764bde5";
+    const LEGACY_TEE_SHARE: &str =
+        "2045c143dbb9609c22ab855d60925a997603d78a3f20d154abd8ddfa6974eedf6b";
+    const LEGACY_VAULT: &str = "0x04ba607bf75e4ba65d8e5b984949b61df3fc4ac65700d5564bd030d0a2cc24a3";
+
+    #[test]
+    fn test_legacy_share_migration_keeps_vault_address() {
+        let legacy = |hex_share: &str| legacy_bytes_to_scalar(&hex::decode(hex_share).unwrap());
+        let dao = legacy(LEGACY_DAO_SHARE).unwrap();
+        let tee = legacy(LEGACY_TEE_SHARE).unwrap();
+        let address = |dao: &SecretScalar, tee: &SecretScalar| {
+            let key = dao.expose_secret() + tee.expose_secret();
+            pubkey_to_sui_address(&VerifyingKey::from(
+                &SigningKey::from_bytes(&key.to_bytes()).unwrap(),
+            ))
+        };
+        assert_eq!(address(&dao, &tee), LEGACY_VAULT);
+
+        // The canonical decoder reads the same bytes as a different key...
+        let current = |hex_share: &str| bytes_to_scalar(&hex::decode(hex_share).unwrap()).unwrap();
+        assert_ne!(
+            address(&current(LEGACY_DAO_SHARE), &current(LEGACY_TEE_SHARE)),
+            LEGACY_VAULT
+        );
+        // ...so migration re-encodes the legacy scalars instead.
+        let migrated = |s: &SecretScalar| {
+            bytes_to_scalar(scalar_to_bytes(s.expose_secret()).unwrap().expose_secret()).unwrap()
+        };
+        assert_eq!(address(&migrated(&dao), &migrated(&tee)), LEGACY_VAULT);
+    }
+
+    #[test]
+    fn test_audit_log_detects_tampering() {
+        let tee = MemberKey::generate("TEE");
+        let tee_address = tee.member().unwrap().address;
+        let alice = MemberKey::generate("Alice").member().unwrap();
+        let mut log = AuditLog::default();
+        log.append(
+            AuditEvent::GenesisInit {
+                threshold: 1,
+                quorum_weight: 1,
+                membership_digest: "00".into(),
+            },
+            1,
+            None,
+        )
+        .unwrap();
+        log.append(
+            AuditEvent::MemberJoined {
+                name: alice.name.clone(),
+                address: alice.address.clone(),
+                scheme: alice.scheme,
+                weight: 1,
+            },
+            2,
+            None,
+        )
+        .unwrap();
+        let vault = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        log.append(
+            AuditEvent::Launched {
+                vault_address: vault.into(),
+                strategy: "Seal".into(),
+                membership_digest: "00".into(),
+                tee_address: tee_address.clone(),
+            },
+            3,
+            Some(&tee),
+        )
+        .unwrap();
+        for id in 1..=3 {
+            log.append(
+                AuditEvent::ProposalExecuted {
+                    id,
+                    executor: alice.address.clone(),
+                    tx_digest: format!("{:02x}", id),
+                    signature: "00".into(),
+                },
+                3 + id,
+                Some(&tee),
+            )
+            .unwrap();
+        }
+        assert_eq!(log.entries[0].prev_hash, GENESIS_HASH);
+        assert_eq!(log.entries[1].prev_hash, log.entries[0].hash);
+        let head = log.head();
+        assert_eq!(head.len, 6);
+        let report = log.verify(Some(&head), None).unwrap();
+        assert_eq!(report.entries, 6);
+        assert_eq!(report.signed, 4);
+        assert_eq!(report.tee_address.as_deref(), Some(tee_address.as_str()));
+        // Entries round-trip through the one-line JSON format.
+        let line = serde_json::to_string(&log.entries[2]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<AuditEntry>(&line).unwrap(),
+            log.entries[2]
+        );
+
+        // Editing an event breaks its hash; rehashing it breaks the chain.
+        let mut edited = log.clone();
+        edited.entries[4].event = AuditEvent::DelegationRevoked { id: "x".into() };
+        assert!(edited.verify(None, None).is_err());
+        edited.entries[4].hash = edited.entries[4].compute_hash().unwrap();
+        assert!(edited.verify(None, None).is_err());
+
+        // Dropping or reordering entries is caught by the sequence numbers.
+        let mut dropped = log.clone();
+        dropped.entries.remove(3);
+        assert!(dropped.verify(None, None).is_err());
+        let mut reordered = log.clone();
+        reordered.entries.swap(3, 4);
+        assert!(reordered.verify(None, None).is_err());
+        let mut headless = log.clone();
+        headless.entries.remove(0);
+        assert!(headless.verify(None, None).is_err());
+
+        // Truncating the tail leaves a valid chain, but not the recorded head.
+        let mut truncated = log.clone();
+        truncated.entries.pop();
+        truncated.verify(None, None).unwrap();
+        assert!(truncated.verify(Some(&head), None).is_err());
+
+        // Only the launched TEE identity may sign; a rewritten log signed by
+        // another key is rejected.
+        let mut forged = log.clone();
+        let other = MemberKey::generate("Mallory");
+        forged.entries[5].tee_signature = Some(
+            other
+                .sign_personal_message(&hex::decode(&forged.entries[5].hash).unwrap())
+                .unwrap(),
+        );
+        assert!(forged.verify(Some(&head), None).is_err());
+        let mut unsigned_launch = AuditLog::default();
+        unsigned_launch
+            .append(
+                AuditEvent::SharesRefreshed {
+                    epoch: 1,
+                    reason: "manual".into(),
+                },
+                1,
+                Some(&tee),
+            )
+            .unwrap();
+        assert!(unsigned_launch.verify(None, None).is_err());
+
+        // After launch every entry must be signed, and only by that identity.
+        let mut stripped = log.clone();
+        stripped.entries[4].tee_signature = None;
+        assert!(stripped.verify(None, None).is_err());
+        let event = AuditEvent::DelegationRevoked { id: "x".into() };
+        assert!(log.clone().append(event.clone(), 7, None).is_err());
+        assert!(log.clone().append(event.clone(), 7, Some(&other)).is_err());
+
+        // A log rewritten around another TEE vouches for itself, but not for
+        // the identity pinned outside it.
+        log.verify(Some(&head), Some(&tee_address)).unwrap();
+        let mallory = other.member().unwrap().address;
+        let mut rewritten = AuditLog::default();
+        rewritten
+            .append(
+                AuditEvent::Launched {
+                    vault_address: vault.into(),
+                    strategy: "Seal".into(),
+                    membership_digest: "00".into(),
+                    tee_address: mallory.clone(),
+                },
+                1,
+                Some(&other),
+            )
+            .unwrap();
+        assert_eq!(
+            rewritten.verify(None, None).unwrap().tee_address,
+            Some(mallory)
+        );
+        assert!(rewritten.verify(None, Some(&tee_address)).is_err());
+        let mut unlaunched = log.clone();
+        unlaunched.entries.truncate(2);
+        assert!(unlaunched.verify(None, Some(&tee_address)).is_err());
+
+        // The TEE signs the head; a head signed by anyone else is rejected.
+        let mut signed_head = head.clone();
+        signed_head.tee_signature =
+            Some(tee.sign_personal_message(&head.digest().unwrap()).unwrap());
+        log.verify(Some(&signed_head), Some(&tee_address)).unwrap();
+        let mut forged_head = head.clone();
+        forged_head.tee_signature = Some(
+            other
+                .sign_personal_message(&head.digest().unwrap())
+                .unwrap(),
+        );
+        assert!(log.verify(Some(&forged_head), None).is_err());
+    }
+
+    #[test]
+    fn test_group_schema_migrations() {
+        let alice = MemberKey::generate("Alice").member().unwrap();
+        let bob = MemberKey::generate_scheme("Bob", SignatureScheme::Ed25519)
+            .member()
+            .unwrap();
+        // Version 1: no schema_version, member defaults left implicit.
+        let v1 = serde_json::json!({
+            "threshold": 1,
+            "members": [
+                {"address": alice.address, "name": "Alice", "pubkey_hex": alice.pubkey_hex},
+                {
+                    "address": bob.address, "name": "Bob", "pubkey_hex": bob.pubkey_hex,
+                    "scheme": "ed25519", "weight": 2, "roles": ["voter"]
+                },
+            ],
+        });
+        assert_eq!(schema_version(&v1).unwrap(), UNVERSIONED);
+
+        let mut migrated = v1.clone();
+        assert_eq!(migrate(&mut migrated).unwrap(), UNVERSIONED);
+        assert_eq!(migrated["schema_version"], GROUP_SCHEMA_VERSION);
+        assert_eq!(migrated["members"][0]["scheme"], "secp256k1");
+        assert_eq!(migrated["members"][0]["weight"], 1);
+        assert_eq!(
+            migrated["members"][0]["roles"],
+            serde_json::json!(["proposer", "voter", "executor"])
+        );
+        // Values the file already spelled out are kept.
+        assert_eq!(migrated["members"][1]["weight"], 2);
+        assert_eq!(
+            migrated["members"][1]["roles"],
+            serde_json::json!(["voter"])
+        );
+
+        let group = parse_group(&v1.to_string()).unwrap();
+        group.validate().unwrap();
+        assert_eq!(group.schema_version, GROUP_SCHEMA_VERSION);
+        assert_eq!(
+            group.members,
+            vec![
+                alice,
+                Member {
+                    weight: 2,
+                    roles: BTreeSet::from([Role::Voter]),
+                    ..bob
+                }
+            ]
+        );
+
+        // Current files need no migration and round-trip unchanged.
+        let saved = serde_json::to_string(&group).unwrap();
+        assert_eq!(
+            migrate(&mut serde_json::from_str(&saved).unwrap()).unwrap(),
+            GROUP_SCHEMA_VERSION
+        );
+        assert_eq!(
+            parse_group(&saved).unwrap().membership_digest(),
+            group.membership_digest()
+        );
+
+        // Files from a newer build, or with a malformed version, are refused.
+        let mut future = migrated.clone();
+        future["schema_version"] = (GROUP_SCHEMA_VERSION + 1).into();
+        let err = parse_group(&future.to_string()).unwrap_err().to_string();
+        assert!(err.contains("only understands up to"), "{}", err);
+        for bad in [
+            serde_json::json!(0),
+            serde_json::json!("2"),
+            serde_json::json!(-1),
+        ] {
+            let mut file = migrated.clone();
+            file["schema_version"] = bad;
+            assert!(parse_group(&file.to_string()).is_err());
+        }
+
+        // Pre-keystore files go through group-migrate-keys first.
+        let mut legacy = v1.clone();
+        legacy["members"][0]["privkey_hex"] = "00".into();
+        let err = parse_group(&legacy.to_string()).unwrap_err().to_string();
+        assert!(err.contains("group-migrate-keys"), "{}", err);
+    }
 }
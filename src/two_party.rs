@@ -0,0 +1,383 @@
+use anyhow::{Result, anyhow};
+use commitment::CommitmentSizedNumber;
+use dwallet_mpc_types::dwallet_mpc::DWalletCurve;
+use group::secp256k1::Scalar as GroupScalar;
+use k256::ecdsa::signature::Verifier;
+use k256::ecdsa::{Signature, VerifyingKey};
+use k256::{AffinePoint, ProjectivePoint};
+use mpc::two_party::{Round, RoundResult};
+use mpc::{
+    AsynchronousRoundResult, AsynchronouslyAdvanceable, PartyID, WeightedThresholdAccessStructure,
+};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use twopc_mpc::secp256k1::class_groups::{AsyncProtocol, DecryptionKey, ProtocolPublicParameters};
+use twopc_mpc::{HashScheme, dkg, presign, sign};
+
+use crate::secret::{SecretBytes, SecretScalar};
+use crate::share_codec::Share;
+use crate::tee_service::TeeMpcService;
+
+// Two-party ECDSA between the DAO share holder and the TEE, on 2PC-MPC
+// (`twopc_mpc`). The vault key s_dao + s_tee is never assembled.
+//
+// The DAO side is the centralized party. The TEE is the decentralized party, a
+// one-member committee whose share is encrypted under its own class-groups key
+// (derived from a seed it keeps next to its share). Every message carries the
+// protocol's zero-knowledge proofs, so neither side has to trust the other to
+// follow it:
+//
+//   setup    The TEE encrypts s_tee and proves it matches s_tee·G; the DAO
+//            proves knowledge of s_dao. Once per pair of shares.
+//   presign  The TEE samples an encrypted nonce. Once per signature.
+//   sign     The DAO sends its partial signature; the TEE decrypts the
+//            result, and it is checked under the joint key.
+//
+// Messages are serde types, so the parties can run on either side of a channel.
+
+/// Size of the seed the TEE's class-groups key is derived from.
+pub const ENCRYPTION_SEED_LEN: usize = 32;
+
+/// The TEE is the only member of the decentralized party.
+const TEE_PARTY_ID: PartyID = 1;
+
+type DkgCentralizedRound = <AsyncProtocol as dkg::Protocol>::DKGCentralizedPartyRound;
+type EncryptShareParty = <AsyncProtocol as dkg::Protocol>::EncryptionOfSecretKeyShareRoundParty;
+type DkgDecentralizedParty = <AsyncProtocol as dkg::Protocol>::ProofVerificationRoundParty;
+type PresignParty = <AsyncProtocol as presign::Protocol>::PresignParty;
+type SignCentralizedParty = <AsyncProtocol as sign::Protocol>::SignCentralizedParty;
+type SignDecentralizedParty = <AsyncProtocol as sign::Protocol>::SignDecentralizedParty;
+
+type EncryptedShare = <AsyncProtocol as dkg::Protocol>::EncryptionOfSecretKeyShareAndPublicKeyShare;
+type PublicKeyShareAndProof = <AsyncProtocol as dkg::Protocol>::PublicKeyShareAndProof;
+type CentralizedKeyShare = <AsyncProtocol as dkg::Protocol>::CentralizedPartySecretKeyShare;
+type CentralizedDkgOutput = <AsyncProtocol as dkg::Protocol>::CentralizedPartyDKGPublicOutput;
+type DecentralizedDkgOutput = <AsyncProtocol as dkg::Protocol>::DecentralizedPartyDKGOutput;
+type Presign = <AsyncProtocol as presign::Protocol>::Presign;
+type SignMessage = <AsyncProtocol as sign::Protocol>::SignMessage;
+
+/// Sent by the TEE side once, when the pair is set up.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TeeSetupMessage {
+    pub protocol_public_parameters: ProtocolPublicParameters,
+    pub session_id: [u8; 32],
+    pub encrypted_share: EncryptedShare,
+    pub public_share: AffinePoint,
+}
+
+/// Sent by the DAO side once, in reply to `TeeSetupMessage`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KeySetupMessage {
+    pub public_key_share_and_proof: PublicKeyShareAndProof,
+    pub public_share: AffinePoint,
+}
+
+/// Round 1 (TEE -> DAO): a fresh presign. Consumed by `finish_signing`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PresignMessage {
+    pub session_id: [u8; 32],
+    pub presign: Presign,
+}
+
+/// Round 2 (DAO -> TEE): the DAO's partial signature over the message.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PartialSignatureMessage {
+    pub sign_message: SignMessage,
+}
+
+/// The DAO share holder: the centralized party.
+pub struct DaoSigningParty {
+    protocol_public_parameters: ProtocolPublicParameters,
+    key_share: CentralizedKeyShare,
+    dkg_output: CentralizedDkgOutput,
+    joint_public_key: VerifyingKey,
+}
+
+impl DaoSigningParty {
+    pub fn new<R: CryptoRng + RngCore>(
+        share: SecretScalar,
+        setup: &TeeSetupMessage,
+        rng: &mut R,
+    ) -> Result<(Self, KeySetupMessage)> {
+        let public_share = ProjectivePoint::GENERATOR * share.expose_secret();
+        let joint_public_key = joint_public_key(&public_share, &setup.public_share)?;
+        let RoundResult {
+            outgoing_message,
+            private_output,
+            public_output,
+        } = DkgCentralizedRound::advance(
+            setup.encrypted_share.clone(),
+            &group_scalar(share)?,
+            &(
+                setup.protocol_public_parameters.clone(),
+                session_id(&setup.session_id),
+            )
+                .into(),
+            rng,
+        )
+        .map_err(|e| anyhow!("Two-party setup failed: {:?}", e))?;
+        Ok((
+            Self {
+                protocol_public_parameters: setup.protocol_public_parameters.clone(),
+                key_share: private_output,
+                dkg_output: public_output,
+                joint_public_key,
+            },
+            KeySetupMessage {
+                public_key_share_and_proof: outgoing_message,
+                public_share: public_share.to_affine(),
+            },
+        ))
+    }
+
+    pub fn joint_public_key(&self) -> VerifyingKey {
+        self.joint_public_key
+    }
+
+    /// Signs `message` (SHA-256, as k256 does) against the TEE's presign.
+    pub fn sign<R: CryptoRng + RngCore>(
+        &self,
+        message: &[u8],
+        presign: &PresignMessage,
+        rng: &mut R,
+    ) -> Result<PartialSignatureMessage> {
+        let result = SignCentralizedParty::advance(
+            (),
+            &self.key_share,
+            &(
+                message.to_vec(),
+                HashScheme::SHA256,
+                self.dkg_output.clone(),
+                presign.presign.clone(),
+                self.protocol_public_parameters.clone(),
+            )
+                .into(),
+            rng,
+        )
+        .map_err(|e| anyhow!("DAO partial signature failed: {:?}", e))?;
+        Ok(PartialSignatureMessage {
+            sign_message: result.outgoing_message,
+        })
+    }
+}
+
+/// The TEE side: the decentralized party, holding its class-groups key.
+pub struct TeeSigningParty {
+    protocol_public_parameters: ProtocolPublicParameters,
+    decryption_key: DecryptionKey,
+    setup: TeeSetupMessage,
+    dkg_output: Option<DecentralizedDkgOutput>,
+    joint_public_key: Option<VerifyingKey>,
+}
+
+impl TeeSigningParty {
+    /// Encrypts `share` under the class-groups key derived from `encryption_seed`.
+    pub fn new<R: CryptoRng + RngCore>(
+        share: SecretScalar,
+        encryption_seed: &SecretBytes,
+        rng: &mut R,
+    ) -> Result<(Self, TeeSetupMessage)> {
+        let seed: [u8; ENCRYPTION_SEED_LEN] =
+            encryption_seed.expose_secret().try_into().map_err(|_| {
+                anyhow!(
+                    "TEE encryption seed must be {} bytes, got {}",
+                    ENCRYPTION_SEED_LEN,
+                    encryption_seed.expose_secret().len()
+                )
+            })?;
+        let (encryption_key, decryption_key) =
+            TeeMpcService::new(DWalletCurve::Secp256k1 as u32).generate_encryption_keypair(seed)?;
+        let protocol_public_parameters =
+            ProtocolPublicParameters::from_encryption_key(&encryption_key)
+                .map_err(|e| anyhow!("Invalid TEE encryption key: {:?}", e))?;
+        let decryption_key: DecryptionKey = bcs::from_bytes(decryption_key.expose_secret())
+            .map_err(|e| anyhow!("Invalid TEE decryption key: {}", e))?;
+
+        let public_share = (ProjectivePoint::GENERATOR * share.expose_secret()).to_affine();
+        let mut session = [0u8; 32];
+        rng.fill_bytes(&mut session);
+        let encrypted_share = run_committee::<EncryptShareParty, _>(
+            &session,
+            Some(group_scalar(share)?),
+            &(protocol_public_parameters.clone(), session_id(&session)).into(),
+            rng,
+        )?;
+        let setup = TeeSetupMessage {
+            protocol_public_parameters: protocol_public_parameters.clone(),
+            session_id: session,
+            encrypted_share,
+            public_share,
+        };
+        Ok((
+            Self {
+                protocol_public_parameters,
+                decryption_key,
+                setup: setup.clone(),
+                dkg_output: None,
+                joint_public_key: None,
+            },
+            setup,
+        ))
+    }
+
+    /// Verifies the DAO's proof and completes the setup.
+    pub fn accept_setup<R: CryptoRng + RngCore>(
+        &mut self,
+        msg: &KeySetupMessage,
+        rng: &mut R,
+    ) -> Result<()> {
+        let dkg_output = run_committee::<DkgDecentralizedParty, _>(
+            &self.setup.session_id,
+            None,
+            &(
+                self.protocol_public_parameters.clone(),
+                session_id(&self.setup.session_id),
+                self.setup.encrypted_share.clone(),
+                msg.public_key_share_and_proof.clone(),
+            )
+                .into(),
+            rng,
+        )?;
+        let own = ProjectivePoint::from(self.setup.public_share);
+        self.joint_public_key = Some(joint_public_key(&own, &msg.public_share)?);
+        self.dkg_output = Some(dkg_output);
+        Ok(())
+    }
+
+    pub fn joint_public_key(&self) -> Result<VerifyingKey> {
+        self.joint_public_key
+            .ok_or(anyhow!("Two-party setup not completed"))
+    }
+
+    pub fn presign<R: CryptoRng + RngCore>(&self, rng: &mut R) -> Result<PresignMessage> {
+        let mut session = [0u8; 32];
+        rng.fill_bytes(&mut session);
+        let presign = run_committee::<PresignParty, _>(
+            &session,
+            None,
+            &(
+                self.protocol_public_parameters.clone(),
+                self.dkg_output()?.clone(),
+            )
+                .into(),
+            rng,
+        )?;
+        Ok(PresignMessage {
+            session_id: session,
+            presign,
+        })
+    }
+
+    /// Completes the DAO's partial signature and checks it under the joint key.
+    pub fn finish_signing<R: CryptoRng + RngCore>(
+        &self,
+        message: &[u8],
+        presign: PresignMessage,
+        msg: &PartialSignatureMessage,
+        rng: &mut R,
+    ) -> Result<Signature> {
+        let (r, s) = run_committee::<SignDecentralizedParty, _>(
+            &presign.session_id,
+            Some(self.decryption_key.clone()),
+            &(
+                message.to_vec(),
+                HashScheme::SHA256,
+                self.dkg_output()?.clone(),
+                presign.presign,
+                msg.sign_message.clone(),
+                self.protocol_public_parameters.clone(),
+            )
+                .into(),
+            rng,
+        )?
+        .into();
+        let r = Share::from_group(&r)?;
+        let s = Share::from_group(&s)?;
+        let signature = Signature::from_scalars(r.scalar().to_bytes(), s.scalar().to_bytes())
+            .map_err(|e| anyhow!("Invalid two-party signature: {}", e))?;
+        let signature = signature.normalize_s().unwrap_or(signature);
+
+        self.joint_public_key()?
+            .verify(message, &signature)
+            .map_err(|e| anyhow!("Two-party signature failed verification: {}", e))?;
+        Ok(signature)
+    }
+
+    fn dkg_output(&self) -> Result<&DecentralizedDkgOutput> {
+        self.dkg_output
+            .as_ref()
+            .ok_or(anyhow!("Two-party setup not completed"))
+    }
+}
+
+/// Runs both parties in-process and returns the signature with the joint public key.
+pub fn two_party_sign<R: CryptoRng + RngCore>(
+    dao_share: SecretScalar,
+    tee_share: SecretScalar,
+    encryption_seed: &SecretBytes,
+    message: &[u8],
+    rng: &mut R,
+) -> Result<(Signature, VerifyingKey)> {
+    let (mut tee, setup) = TeeSigningParty::new(tee_share, encryption_seed, rng)?;
+    let (dao, key_setup) = DaoSigningParty::new(dao_share, &setup, rng)?;
+    tee.accept_setup(&key_setup, rng)?;
+
+    let presign = tee.presign(rng)?;
+    let partial = dao.sign(message, &presign, rng)?;
+    let signature = tee.finish_signing(message, presign, &partial, rng)?;
+    Ok((signature, dao.joint_public_key()))
+}
+
+/// Drives a decentralized-party protocol with the TEE as the committee's only
+/// member: each round's message is fed back in until the protocol finalizes.
+fn run_committee<P, R>(
+    session: &[u8; 32],
+    private_input: Option<P::PrivateInput>,
+    public_input: &P::PublicInput,
+    rng: &mut R,
+) -> Result<P::PublicOutput>
+where
+    P: AsynchronouslyAdvanceable,
+    P::PrivateInput: Clone,
+    P::Message: Clone,
+    P::Error: std::fmt::Debug,
+    R: CryptoRng + RngCore,
+{
+    let access_structure =
+        WeightedThresholdAccessStructure::new(1, HashMap::from([(TEE_PARTY_ID, 1)]))
+            .map_err(|e| anyhow!("Invalid TEE committee: {:?}", e))?;
+    let mut messages = Vec::new();
+    loop {
+        match P::advance(
+            session_id(session),
+            TEE_PARTY_ID,
+            &access_structure,
+            messages.clone(),
+            private_input.clone(),
+            public_input,
+            rng,
+        )
+        .map_err(|e| anyhow!("TEE protocol round failed: {:?}", e))?
+        {
+            AsynchronousRoundResult::Advance { message, .. } => {
+                messages.push(HashMap::from([(TEE_PARTY_ID, message)]))
+            }
+            AsynchronousRoundResult::Finalize { public_output, .. } => return Ok(public_output),
+        }
+    }
+}
+
+fn session_id(session: &[u8; 32]) -> CommitmentSizedNumber {
+    CommitmentSizedNumber::from_le_slice(session)
+}
+
+fn group_scalar(share: SecretScalar) -> Result<GroupScalar> {
+    Ok(Share::from_scalar(*share.expose_secret()).to_group()?)
+}
+
+fn joint_public_key(own: &ProjectivePoint, peer: &AffinePoint) -> Result<VerifyingKey> {
+    let joint = *own + ProjectivePoint::from(*peer);
+    VerifyingKey::from_affine(joint.to_affine())
+        .map_err(|e| anyhow!("Invalid joint public key: {}", e))
+}